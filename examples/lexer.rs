@@ -1,11 +1,10 @@
 use std::cell::{Cell, RefCell};
 use std::fmt::Debug;
-use metal_programming_language::core::{node, token};
-use metal_programming_language::core::node::{identifier, number, r#type, string, whitespace, Error, ErrorKind, NodeVariant, Parsable, Traverser};
+use metal_programming_language::core::{line, node};
+use metal_programming_language::core::node::{character, identifier, number, r#type, string, whitespace, Error, ErrorKind, NodeVariant, Parsable, Traverser};
 use inline_colorization::*;
-use metal_programming_language::core::node::number::Number;
+use metal_programming_language::core::node::number::Value;
 use metal_programming_language::core::node::string::Node;
-use metal_programming_language::core::token::{Kind, Token};
 
 fn indent(line: usize) {
     print!("  {:02}  ", line);
@@ -22,23 +21,23 @@ fn print_error<Other: Debug + PartialEq>(error: node::Error<Other>, source: &str
     //     _ => todo!()
     // }
     
-    eprintln!("Input:");
-    let stream = token::Iterator::from_str(source);
+    let index = line::Index::from_str(source);
+    let Some(position) = error.position(&index) else { return };
+    eprintln!("Input at {}:{}:", position.line, position.column);
     
-    indent(0);
-    
-    let mut line = 0usize;
-    
-    for (index, token) in stream.enumerate() {
-        if index == error.start_token { print!("{color_red}{}{color_reset}", token.kind()) }
-        else { print!("{}", token.kind()) }
+    for line in 1..=index.line_count() {
+        let text = index.line(line).unwrap();
+        indent(line);
         
-        if let Kind::NewLine = token.kind() {
-            line += 1;
-            indent(line);
-            
+        if line != position.line {
+            println!("{}", text);
             continue
         }
+        
+        let (before, after) = text.split_at(position.column - 1);
+        let mut after = after.chars();
+        let highlighted = after.next().map(String::from).unwrap_or_default();
+        println!("{}{color_red}{}{color_reset}{}", before, highlighted, after.as_str());
     }
 }
 
@@ -54,9 +53,9 @@ enum Color {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-struct DecoratedToken<'a> {
+struct DecoratedSlice<'a> {
     color: Color,
-    token: Token<'a>
+    slice: &'a str
 }
 
 fn try_node<'a, Node: Parsable<'a>, Other: Debug + PartialEq>(output: &mut Vec<NodeVariant<'a>>, tokens: &mut Traverser<'a>, mut parse: impl FnMut(&mut Traverser<'a>) -> Result<NodeVariant<'a>, Error<Other>>) -> Result<(), Error<Other>> {
@@ -72,58 +71,46 @@ fn main() {
     loop {
         if try_node::<whitespace::Node, <whitespace::Node as Parsable>::Error>(&mut nodes, &mut tokens, |x| Ok(NodeVariant::WhiteSpace(whitespace::Node::parse(x)?))).is_ok() { continue }
         if try_node::<string::Node, <string::Node as Parsable>::Error>(&mut nodes, &mut tokens, |x| Ok(NodeVariant::String(string::Node::parse(x)?))).is_ok() { continue }
+        if try_node::<character::Node, <character::Node as Parsable>::Error>(&mut nodes, &mut tokens, |x| Ok(NodeVariant::Character(character::Node::parse(x)?))).is_ok() { continue }
         if try_node::<identifier::Node, <identifier::Node as Parsable>::Error>(&mut nodes, &mut tokens, |x| Ok(NodeVariant::Identifier(identifier::Node::parse(x)?))).is_ok() { continue }
         if try_node::<number::Node, <number::Node as Parsable>::Error>(&mut nodes, &mut tokens, |x| Ok(NodeVariant::Number(number::Node::parse(x)?))).is_ok() { continue }
         if try_node::<r#type::Node, <r#type::Node as Parsable>::Error>(&mut nodes, &mut tokens, |x| Ok(NodeVariant::Type(r#type::Node::parse(x)?))).is_ok() { continue }
         break
     }
     
-    let mut source_tokens = Traverser::from_str(source);
-    let mut nodes = nodes.iter();
+    let nodes = nodes.iter();
     let mut colored = Vec::new();
     
     for node in nodes {
-        let (color, start, end) = match node {
-            NodeVariant::WhiteSpace(n) => (Color::White, n.start_token(), n.end_token()),
-            NodeVariant::String(n) => (Color::Red, n.start_token(), n.end_token()),
-            NodeVariant::Identifier(id) => (Color::Cyan, id.start_token(), id.end_token()),
+        let (color, range) = match node {
+            NodeVariant::WhiteSpace(n) => (Color::White, n.byte_range()),
+            NodeVariant::String(n) => (Color::Red, n.byte_range()),
+            NodeVariant::Character(n) => (Color::Red, n.byte_range()),
+            NodeVariant::Identifier(id) => (Color::Cyan, id.byte_range()),
             NodeVariant::Number(id) => {
-                let color = match id.data() {
-                    Number::UnSigned(_) => Color::Yellow,
-                    Number::Signed(_) => Color::Green,
-                    Number::Float(_) => Color::Purple
+                let color = match id.data().value {
+                    Value::Integer(ref integer) if integer.is_negative() => Color::Green,
+                    Value::UnSigned(_) | Value::Integer(_) => Color::Yellow,
+                    Value::Signed(_) => Color::Green,
+                    Value::Float(_) => Color::Purple
                 };
-                (color, id.start_token(), id.end_token())
+                (color, id.byte_range())
             }
-            NodeVariant::Type(id) => (Color::Aqua, id.start_token(), id.end_token()),
+            NodeVariant::Type(id) => (Color::Aqua, id.byte_range()),
         };
         
-        let offset = start - source_tokens.token_offset();
-        for _ in 0..offset { source_tokens.next().unwrap(); }
-        let displacement = end - start;
-        
-        for _ in 0..displacement {
-            let token = source_tokens.next().unwrap();
-            colored.push(DecoratedToken { color: color.clone(), token })
-        }
-        
-        // gets all tokens that fit between node.start()..node.end()
-        
-        // colored.push(DecoratedToken {
-        //     color,
-        //     token: node
-        // })
+        colored.push(DecoratedSlice { color, slice: &source[range] });
     }
     
-    for token in colored {
-        match token.color {
-            Color::Red => print!("{color_red}{}{color_reset}", token.token.kind()),
-            Color::White => print!("{}", token.token.kind()),
-            Color::Cyan => print!("{color_cyan}{}{color_reset}", token.token.kind()),
-            Color::Purple => print!("{color_magenta}{}{color_reset}", token.token.kind()),
-            Color::Green => print!("{color_green}{}{color_reset}", token.token.kind()),
-            Color::Yellow => print!("{color_yellow}{}{color_reset}", token.token.kind()),
-            Color::Aqua => print!("{color_bright_cyan}{}{color_reset}", token.token.kind())
+    for slice in colored {
+        match slice.color {
+            Color::Red => print!("{color_red}{}{color_reset}", slice.slice),
+            Color::White => print!("{}", slice.slice),
+            Color::Cyan => print!("{color_cyan}{}{color_reset}", slice.slice),
+            Color::Purple => print!("{color_magenta}{}{color_reset}", slice.slice),
+            Color::Green => print!("{color_green}{}{color_reset}", slice.slice),
+            Color::Yellow => print!("{color_yellow}{}{color_reset}", slice.slice),
+            Color::Aqua => print!("{color_bright_cyan}{}{color_reset}", slice.slice)
         }
     }
 }
\ No newline at end of file
@@ -12,16 +12,10 @@ fn main() {
         let painted = match token {
             Kind::Space => token_string.white(),
             Kind::Tab => token_string.white(),
-            Kind::NewLine => token_string.white(),
-            Kind::Identifier(i) => match i {
-                "fun" 
-                | "var"
-                | "struct"
-                | "derive"
-                | "depend" => token_string.red(),
-                _ => token_string.white()
-            },
-            Kind::Digit(_) => token_string.cyan(),
+            Kind::NewLine(_) => token_string.white(),
+            Kind::Identifier(_) => token_string.white(),
+            Kind::Keyword(_) => token_string.red(),
+            Kind::Number(_) => token_string.cyan(),
             Kind::OpeningBracket => token_string.white(),
             Kind::ClosingBracket => token_string.white(),
             Kind::OpeningChevron => token_string.white(),
@@ -33,9 +27,16 @@ fn main() {
             Kind::Separator => token_string.cyan(),
             Kind::Equal => token_string.cyan(),
             Kind::StringQuote => token_string.green(),
+            Kind::MultiLineQuote => token_string.green(),
+            Kind::RawString(_) => token_string.green(),
             Kind::CharacterQuote => token_string.green(),
             Kind::Escape => token_string.yellow(),
-            Kind::Comment => token_string.bright_black(),
+            Kind::StringText(_) => token_string.green(),
+            Kind::EscapeSequence(_) => token_string.yellow(),
+            Kind::InterpolationStart => token_string.bright_yellow(),
+            Kind::InterpolationEnd => token_string.bright_yellow(),
+            Kind::Comment(_) => token_string.bright_black(),
+            Kind::DocComment(_) => token_string.bright_green(),
             Kind::Other(_) => token_string.magenta()
         };
         
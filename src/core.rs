@@ -1,2 +1,8 @@
 pub mod token;
-pub mod node;
\ No newline at end of file
+pub mod node;
+pub mod line;
+pub mod source;
+pub mod symbol;
+pub mod incremental;
+pub mod stream;
+pub mod lint;
\ No newline at end of file
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod test;
+
+use std::ops::Range;
+use crate::core::token;
+use crate::core::token::{Diagnostic, Dialect, State, Token};
+
+/// A text edit, replacing a byte range of the old source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edit<'a> {
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub replacement: &'a str
+}
+
+impl Edit<'_> {
+    pub const fn byte_range(&self) -> Range<usize> { self.byte_start..self.byte_end }
+
+    /// Apply the edit to the source it was made against.
+    pub fn apply(&self, source: &str) -> String {
+        let mut text = String::with_capacity(source.len() - (self.byte_end - self.byte_start) + self.replacement.len());
+        text.push_str(&source[..self.byte_start]);
+        text.push_str(self.replacement);
+        text.push_str(&source[self.byte_end..]);
+        text
+    }
+
+    /// How far bytes after the edit moved.
+    const fn shift(&self, byte: usize) -> usize {
+        byte + self.replacement.len() - (self.byte_end - self.byte_start)
+    }
+}
+
+/// The tokens that were replaced by re-lexing. Tokens before `old.start` are unchanged, tokens from
+/// `old.end` onwards are unchanged apart from being moved by the edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub old: Range<usize>,
+    pub new: Range<usize>
+}
+
+/// The tokens of a whole source, along with the lexer state before each of them so that an edit can
+/// be re-lexed without lexing the rest of the source again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokens<'a> {
+    source: &'a str,
+    tokens: Vec<Token<'a>>,
+    states: Vec<State>,
+    dialect: Dialect,
+    diagnostics: Vec<Diagnostic>
+}
+
+impl<'a> Tokens<'a> {
+    pub fn from_str(value: &'a str) -> Self {
+        Self::with_dialect(value, Dialect::DEFAULT)
+    }
+
+    pub fn with_dialect(value: &'a str, dialect: Dialect) -> Self {
+        let mut iterator = token::Iterator::with_dialect(value, dialect);
+        let (tokens, states) = Self::lex(&mut iterator, |_, _| false);
+        Self { source: value, tokens, states, dialect, diagnostics: iterator.take_diagnostics() }
+    }
+
+    pub const fn source(&self) -> &'a str { self.source }
+    pub fn tokens(&self) -> &[Token<'a>] { &self.tokens }
+    pub fn diagnostics(&self) -> &[Diagnostic] { &self.diagnostics }
+
+    /// Lex tokens until the iterator ends or `synchronized` accepts the boundary after a token.
+    fn lex<'b>(iterator: &mut token::Iterator<'b>, mut synchronized: impl FnMut(usize, &State) -> bool) -> (Vec<Token<'b>>, Vec<State>) {
+        let mut tokens = Vec::new();
+        let mut states = Vec::new();
+
+        loop {
+            let state = iterator.state().clone();
+            let Some(token) = iterator.next() else { break };
+            tokens.push(token);
+            states.push(state);
+            if synchronized(token.byte_end(), iterator.state()) { break }
+        }
+
+        (tokens, states)
+    }
+
+    /// Re-lex the tokens around an edit. `source` is the edited text, as returned by [`Edit::apply`].
+    /// Lexing restarts at the first token whose lookahead reaches the start of the edit and stops as
+    /// soon as it reaches a boundary after the edit where an old token began with the same lexer state.
+    pub fn relex<'b>(&self, source: &'b str, edit: &Edit) -> (Tokens<'b>, Change) {
+        // a token can change with the text after it, as `""` does when another `"` is typed.
+        let first = self.tokens.partition_point(|token| {
+            token.byte_end() + token::Iterator::lookahead(self.source, token) <= edit.byte_start
+        });
+        let restart = self.tokens.get(first).map_or(edit.byte_start, Token::byte_start);
+        let restart_state = self.states.get(first).cloned().unwrap_or_default();
+
+        // old tokens that start after the edit, and so may be reused.
+        let mut reusable = self.tokens.partition_point(|token| token.byte_start() < edit.byte_end);
+        let mut last = self.tokens.len();
+        let mut iterator = token::Iterator::resume(source, restart, restart_state, self.dialect);
+        let (relexed, relexed_states) = Self::lex(&mut iterator, |byte_end, state| {
+            while self.tokens.get(reusable).is_some_and(|token| edit.shift(token.byte_start()) < byte_end) { reusable += 1 }
+            let Some(token) = self.tokens.get(reusable) else { return false };
+            if edit.shift(token.byte_start()) != byte_end || self.states[reusable] != *state { return false }
+            last = reusable;
+            true
+        });
+
+        let mut tokens = Vec::with_capacity(first + relexed.len() + self.tokens.len() - last);
+        tokens.extend(self.tokens[..first].iter().map(|token| token.rebase(source, token.byte_start())));
+        tokens.extend_from_slice(&relexed);
+        tokens.extend(self.tokens[last..].iter().map(|token| token.rebase(source, edit.shift(token.byte_start()))));
+
+        let mut states = Vec::with_capacity(tokens.len());
+        states.extend_from_slice(&self.states[..first]);
+        states.extend_from_slice(&relexed_states);
+        states.extend_from_slice(&self.states[last..]);
+
+        let reused_start = self.tokens.get(last).map_or(self.source.len(), Token::byte_start);
+        let mut diagnostics = self.diagnostics.iter().filter(|diagnostic| diagnostic.byte_range.start < restart).cloned().collect::<Vec<_>>();
+        diagnostics.extend(iterator.take_diagnostics());
+        diagnostics.extend(self.diagnostics.iter().filter(|diagnostic| diagnostic.byte_range.start >= reused_start).map(|diagnostic| Diagnostic {
+            error: diagnostic.error,
+            byte_range: edit.shift(diagnostic.byte_range.start)..edit.shift(diagnostic.byte_range.end)
+        }));
+
+        let change = Change { old: first..last, new: first..first + relexed.len() };
+        (Tokens { source, tokens, states, dialect: self.dialect, diagnostics }, change)
+    }
+}
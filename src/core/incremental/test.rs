@@ -0,0 +1,29 @@
+use crate::core::incremental::{Change, Edit, Tokens};
+use crate::core::token::{Iterator, Token};
+
+fn relex(source: &str, edit: &Edit) -> Change {
+    let tokens = Tokens::from_str(source);
+    let edited = edit.apply(source);
+    let (relexed, change) = tokens.relex(&edited, edit);
+
+    let expected = Tokens::from_str(&edited);
+    assert_eq!(relexed.tokens(), expected.tokens());
+    assert_eq!(relexed.diagnostics(), expected.diagnostics());
+    assert_eq!(relexed.tokens(), Iterator::from_str(&edited).collect::<Vec<Token>>());
+    change
+}
+
+#[test]
+fn edit() {
+    // renaming `b` to `bcd` only re-lexes it and the space it could have joined.
+    assert_eq!(relex("var a = b @ c", &Edit { byte_start: 8, byte_end: 9, replacement: "bcd" }), Change { old: 5..7, new: 5..7 });
+    // joining two identifiers.
+    assert_eq!(relex("ab cd", &Edit { byte_start: 2, byte_end: 3, replacement: "" }), Change { old: 0..3, new: 0..1 });
+    // opening a string changes the meaning of everything after it.
+    assert_eq!(relex("a // b\nc @", &Edit { byte_start: 1, byte_end: 1, replacement: "\"" }).old, 0..7);
+    assert_eq!(relex("", &Edit { byte_start: 0, byte_end: 0, replacement: "x\r" }), Change { old: 0..0, new: 0..2 });
+    assert_eq!(relex("x\r", &Edit { byte_start: 2, byte_end: 2, replacement: "\n" }), Change { old: 1..2, new: 1..2 });
+    // tokens before the edit can become a multi-line quote or the start of a raw string.
+    assert_eq!(relex("\"\" x", &Edit { byte_start: 2, byte_end: 2, replacement: "\"" }).old.start, 0);
+    assert_eq!(relex("a ##", &Edit { byte_start: 4, byte_end: 4, replacement: "\"a\"##" }).old.start, 2);
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod test;
+
+use std::ops::Range;
+
+/// A 1-based location in the source. The column is counted both in UTF-8 bytes and in UTF-16 code
+/// units, since editors disagree on which one they expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub utf16_column: usize
+}
+
+/// Maps byte offsets in a source string to line and column positions and back, without having to
+/// lex the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Index<'a> {
+    source: &'a str,
+    line_starts: Box<[usize]>
+}
+
+impl<'a> Index<'a> {
+    pub fn from_str(value: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        let mut bytes = value.bytes().enumerate().peekable();
+
+        // a line ends at `\n`, `\r\n` or a lone `\r`, matching the lexer's `Kind::NewLine`.
+        while let Some((offset, byte)) = bytes.next() {
+            match byte {
+                b'\n' => line_starts.push(offset + 1),
+                b'\r' => if bytes.next_if(|&(_, next)| next == b'\n').is_some() {
+                    line_starts.push(offset + 2)
+                } else {
+                    line_starts.push(offset + 1)
+                },
+                _ => ()
+            }
+        }
+
+        Self { source: value, line_starts: line_starts.into_boxed_slice() }
+    }
+
+    pub const fn source(&self) -> &'a str { self.source }
+    pub const fn line_count(&self) -> usize { self.line_starts.len() }
+
+    /// The byte range of a 1-based line, excluding its line ending.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self.line_starts
+            .get(line)
+            .map_or(self.source.len(), |&next| {
+                if self.source[..next].ends_with("\r\n") { next - 2 } else { next - 1 }
+            });
+        Some(start..end)
+    }
+
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        Some(&self.source[self.line_range(line)?])
+    }
+
+    /// Find the position of a byte offset. Offsets that fall inside a multibyte character or past the
+    /// end of the source have no position.
+    pub fn position(&self, byte_offset: usize) -> Option<Position> {
+        if !self.source.is_char_boundary(byte_offset) { return None }
+
+        let line = self.line_starts.partition_point(|&start| start <= byte_offset);
+        let line_start = self.line_starts[line - 1];
+        let prefix = &self.source[line_start..byte_offset];
+
+        Some(Position {
+            line,
+            column: prefix.len() + 1,
+            utf16_column: prefix.encode_utf16().count() + 1
+        })
+    }
+
+    /// Find the byte offset of a 1-based line and UTF-8 column.
+    pub fn byte_offset(&self, line: usize, column: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let offset = range.start + column.checked_sub(1)?;
+        if offset > range.end || !self.source.is_char_boundary(offset) { return None }
+        Some(offset)
+    }
+
+    /// Find the byte offset of a 1-based line and UTF-16 column.
+    pub fn utf16_byte_offset(&self, line: usize, utf16_column: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let mut remaining = utf16_column.checked_sub(1)?;
+
+        for (offset, character) in self.source[range.clone()].char_indices() {
+            if remaining == 0 { return Some(range.start + offset) }
+            remaining = remaining.checked_sub(character.len_utf16())?;
+        }
+
+        if remaining == 0 { return Some(range.end) }
+        None
+    }
+}
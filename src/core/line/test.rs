@@ -0,0 +1,29 @@
+use crate::core::line::{Index, Position};
+
+#[test]
+fn position() {
+    let index = Index::from_str("var x\n\"ünï\" 10\n");
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.position(0), Some(Position { line: 1, column: 1, utf16_column: 1 }));
+    assert_eq!(index.position(13), Some(Position { line: 2, column: 8, utf16_column: 6 }));
+    assert_eq!(index.position(8), None);
+    assert_eq!(index.line(2), Some("\"ünï\" 10"));
+}
+
+#[test]
+fn byte_offset() {
+    let index = Index::from_str("a\n😀b");
+    assert_eq!(index.byte_offset(2, 5), Some(6));
+    assert_eq!(index.utf16_byte_offset(2, 3), Some(6));
+    assert_eq!(index.utf16_byte_offset(2, 2), None);
+    assert_eq!(index.byte_offset(3, 1), None);
+}
+
+#[test]
+fn line_endings() {
+    let index = Index::from_str("a\r\nb\rc\nd");
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line(1), Some("a"));
+    assert_eq!(index.line(2), Some("b"));
+    assert_eq!(index.position(5), Some(Position { line: 3, column: 1, utf16_column: 1 }));
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::HashMap;
+use std::ops::Range;
+use thiserror::Error;
+use unicode_security::{skeleton, MixedScript};
+use crate::core::symbol::normalize;
+use crate::core::token::{Kind, Token};
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum WarningKind<'a> {
+    #[error("The identifier `{name}` looks like `{original}` but is a different name")]
+    ConfusableIdentifier {
+        name: &'a str,
+        original: &'a str,
+        original_range: Range<usize>
+    },
+    #[error("The identifier `{0}` mixes characters from several scripts")]
+    MixedScriptIdentifier(&'a str),
+    #[error("Bidirectional control character U+{:04X} can make the source display differently than it is read", *.0 as u32)]
+    BidirectionalControl(char)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning<'a> {
+    pub kind: WarningKind<'a>,
+    pub byte_range: Range<usize>
+}
+
+/// Characters that reorder how text is displayed, and can hide what the source really says.
+pub const BIDIRECTIONAL_CONTROLS: [char; 12] = [
+    '\u{061C}', '\u{200E}', '\u{200F}',
+    '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}',
+    '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}'
+];
+
+/// Lint the tokens of one file for identifiers that can be mistaken for each other, identifiers that
+/// mix scripts and bidirectional control characters hidden in strings, raw strings or comments.
+pub fn lint<'a>(tokens: impl IntoIterator<Item = Token<'a>>) -> Vec<Warning<'a>> {
+    let mut warnings = Vec::new();
+    let mut skeletons: HashMap<String, (&'a str, Range<usize>)> = HashMap::new();
+
+    for token in tokens {
+        match *token.kind() {
+            Kind::Identifier(name) => {
+                if !name.is_single_script() {
+                    warnings.push(Warning { kind: WarningKind::MixedScriptIdentifier(name), byte_range: token.byte_range() });
+                }
+
+                let normalized = normalize(name);
+                let key = skeleton(&normalized).collect::<String>();
+                let (original, original_range) = skeletons.entry(key).or_insert((name, token.byte_range())).clone();
+
+                if normalize(original) != normalized {
+                    let kind = WarningKind::ConfusableIdentifier { name, original, original_range };
+                    warnings.push(Warning { kind, byte_range: token.byte_range() });
+                }
+            },
+            Kind::StringText(text) | Kind::Comment(text) => lint_bidirectional(&mut warnings, text, token.byte_start()),
+            Kind::DocComment(comment) => lint_bidirectional(&mut warnings, comment.text, token.byte_start()),
+            Kind::RawString(string) => lint_bidirectional(&mut warnings, string.text, token.byte_start()),
+            Kind::Other(character) if BIDIRECTIONAL_CONTROLS.contains(&character) => {
+                warnings.push(Warning { kind: WarningKind::BidirectionalControl(character), byte_range: token.byte_range() });
+            },
+            _ => ()
+        }
+    }
+
+    warnings
+}
+
+fn lint_bidirectional<'a>(warnings: &mut Vec<Warning<'a>>, text: &'a str, byte_start: usize) {
+    for (offset, character) in text.char_indices().filter(|(_, character)| BIDIRECTIONAL_CONTROLS.contains(character)) {
+        let start = byte_start + offset;
+        warnings.push(Warning { kind: WarningKind::BidirectionalControl(character), byte_range: start..start + character.len_utf8() });
+    }
+}
@@ -0,0 +1,24 @@
+use crate::core::lint::{lint, Warning, WarningKind};
+use crate::core::token::Iterator;
+
+#[test]
+fn confusable() {
+    // the second `ape` is spelled entirely in Cyrillic, `vаr` has a Cyrillic `а`.
+    let source = "ape \u{430}\u{440}\u{435} ape v\u{430}r \"\u{202E}\"";
+    let warnings = lint(Iterator::from_str(source));
+    
+    assert_eq!(warnings, [
+        Warning {
+            kind: WarningKind::ConfusableIdentifier { name: "\u{430}\u{440}\u{435}", original: "ape", original_range: 0..3 },
+            byte_range: 4..10
+        },
+        Warning { kind: WarningKind::MixedScriptIdentifier("v\u{430}r"), byte_range: 15..19 },
+        Warning { kind: WarningKind::BidirectionalControl('\u{202E}'), byte_range: 21..24 }
+    ]);
+}
+
+#[test]
+fn bidirectional_in_raw_string() {
+    let warnings = lint(Iterator::from_str("#\"\u{202E}\"#"));
+    assert_eq!(warnings, [Warning { kind: WarningKind::BidirectionalControl('\u{202E}'), byte_range: 2..5 }]);
+}
@@ -1,5 +1,7 @@
 pub mod identifier;
 pub mod string;
+pub mod character;
+pub mod escape;
 pub mod whitespace;
 pub mod number;
 pub mod r#type;
@@ -9,16 +11,18 @@ pub mod declaration;
 mod test;
 
 use std::fmt::Debug;
-use std::iter::Peekable;
+use std::ops::Range;
 use thiserror::Error;
-use crate::core::{node, token};
-use crate::core::token::{Kind, Token};
+use crate::core::{line, node, token};
+use crate::core::symbol::Interner;
+use crate::core::token::{Dialect, Kind, Token};
 use crate::Pbt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NodeKind {
     WhiteSpace,
     String,
+    Character,
     Identifier,
     Number,
     Type
@@ -28,22 +32,34 @@ pub enum NodeKind {
 pub enum NodeVariant<'a> {
     WhiteSpace(whitespace::Node),
     String(string::Node<'a>),
+    Character(character::Node),
     Identifier(identifier::Node<'a>),
     Number(number::Node),
     Type(r#type::Node<'a>)
 }
 
+/// A position in the token stream, recorded both as a token index and as an absolute byte offset
+/// into the source.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Offset {
+    pub token: usize,
+    pub byte: usize
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Node<Data: Debug + Clone + PartialEq> {
-    pub(self) start_token: usize,
-    pub(self) end_token: usize,
+    pub(self) byte_start: usize,
+    pub(self) byte_end: usize,
     pub(self) data: Data
 }
 
 impl<Data: Debug + Clone + PartialEq> Node<Data> {
-    pub const fn start_token(&self) -> usize { self.start_token }
-    pub const fn end_token(&self) -> usize { self.end_token }
+    pub const fn byte_start(&self) -> usize { self.byte_start }
+    pub const fn byte_end(&self) -> usize { self.byte_end }
     pub const fn data(&self) -> &Data { &self.data }
+
+    /// The range of bytes this node covers in [`Traverser::source`].
+    pub const fn byte_range(&self) -> Range<usize> { self.byte_start..self.byte_end }
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -62,26 +78,57 @@ pub enum ErrorKind<Other: Debug + PartialEq> {
 #[error("Failed to parse a node")]
 pub struct Error<Other: Debug + PartialEq> {
     pub kind: ErrorKind<Other>,
-    pub start_token: usize
+    pub start_token: usize,
+    pub byte_offset: usize
+}
+
+impl<Other: Debug + PartialEq> Error<Other> {
+    /// Create an error that points at an earlier offset, such as the start of the node being parsed.
+    pub const fn at(offset: Offset, kind: ErrorKind<Other>) -> Self {
+        Self {
+            kind,
+            start_token: offset.token,
+            byte_offset: offset.byte
+        }
+    }
+
+    pub fn position(&self, index: &line::Index) -> Option<line::Position> {
+        index.position(self.byte_offset)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Traverser<'a> {
-    tokens: Peekable<token::Iterator<'a>>,
+    tokens: token::Iterator<'a>,
+    peeked: Option<Token<'a>>,
     token_offset: usize,
     source: &'a str,
-    string_byte_offset: usize
+    byte_offset: usize,
+    interner: Interner
 }
 
 impl<'a> Traverser<'a> {
     pub const fn token_offset(&self) -> usize { self.token_offset }
-    pub const fn byte_offset(&self) -> usize { self.string_byte_offset }
     pub const fn source(&self) -> &'a str { self.source }
+    pub const fn interner(&self) -> &Interner { &self.interner }
+    pub const fn dialect(&self) -> Dialect { self.tokens.dialect() }
+
+    /// The absolute byte offset of the end of the last consumed token, which is also where the next
+    /// token begins.
+    pub const fn byte_offset(&self) -> usize { self.byte_offset }
+    
+    pub const fn offset(&self) -> Offset {
+        Offset {
+            token: self.token_offset,
+            byte: self.byte_offset
+        }
+    }
 
     pub const fn new_error<Other: Debug + PartialEq>(&self, kind: ErrorKind<Other>) -> Error<Other> {
         Error {
             kind,
             start_token: self.token_offset,
+            byte_offset: self.byte_offset
         }
     }
 
@@ -89,10 +136,10 @@ impl<'a> Traverser<'a> {
         self.new_error(ErrorKind::Other(other))
     }
 
-    pub const fn end<Other: Debug + PartialEq, Data: Debug + Clone + PartialEq>(&self, start: usize, data: Data) -> Result<Node<Data>, Error<Other>> {
+    pub const fn end<Other: Debug + PartialEq, Data: Debug + Clone + PartialEq>(&self, start: Offset, data: Data) -> Result<Node<Data>, Error<Other>> {
         Ok(Node {
-            start_token: start,
-            end_token: self.token_offset,
+            byte_start: start.byte,
+            byte_end: self.byte_offset,
             data
         })
     }
@@ -110,12 +157,7 @@ impl<'a> Traverser<'a> {
     }
 
     pub fn expect_tokens<Other: Debug + PartialEq>(&mut self, tokens: &[Kind<'a>]) -> Result<Token<'a>, Error<Other>> {
-        let peeked = self.tokens
-            .peek()
-            .ok_or(Error {
-                start_token: self.token_offset,
-                kind: ErrorKind::ReachedEndForToken,
-            })?;
+        let Some(peeked) = self.peek() else { return Err(self.new_error(ErrorKind::ReachedEndForToken)) };
 
         for &token in tokens { if peeked.kind() == &token {
             let matched_token = self.next().unwrap();
@@ -126,12 +168,7 @@ impl<'a> Traverser<'a> {
     }
 
     pub fn expect_token<Other: Debug + PartialEq>(&mut self, token: &Kind<'a>) -> Result<Token<'a>, Error<Other>> {
-        let peeked = self.tokens
-            .peek()
-            .ok_or(Error {
-                start_token: self.token_offset,
-                kind: ErrorKind::ReachedEndForToken,
-            })?;
+        let Some(peeked) = self.peek() else { return Err(self.new_error(ErrorKind::ReachedEndForToken)) };
         
         if peeked.kind() == token {
             let matched_token = self.next().unwrap();
@@ -153,18 +190,33 @@ impl<'a> Traverser<'a> {
     }
 
     pub fn peek(&mut self) -> Option<&Token<'a>> {
-        self.tokens.peek()
+        if self.peeked.is_none() { self.peeked = self.tokens.next() }
+        self.peeked.as_ref()
     }
 
+    /// Problems the lexer found in the tokens read so far, including the peeked token.
+    pub fn diagnostics(&self) -> &[token::Diagnostic] { self.tokens.diagnostics() }
+
     pub fn from_str(value: &'a str) -> Self {
-        let tokens = token::Iterator::from_str(value);
+        Self::with_interner(value, Interner::default())
+    }
+
+    /// Create a traverser that interns identifiers into an interner shared with other files.
+    pub fn with_interner(value: &'a str, interner: Interner) -> Self {
+        Self::with_dialect(value, interner, Dialect::DEFAULT)
+    }
+
+    pub fn with_dialect(value: &'a str, interner: Interner, dialect: Dialect) -> Self {
+        let tokens = token::Iterator::with_dialect(value, dialect);
         let source = tokens.source();
 
         Self {
             source,
-            tokens: tokens.peekable(),
+            tokens,
+            peeked: None,
             token_offset: 0,
-            string_byte_offset: 0
+            byte_offset: 0,
+            interner
         }
     }
 }
@@ -172,9 +224,9 @@ impl<'a> Traverser<'a> {
 impl<'a> Iterator for Traverser<'a> {
     type Item = Token<'a>;
     fn next(&mut self) -> Option<Self::Item> {
-        let result = self.tokens.next()?;
+        let result = self.peeked.take().or_else(|| self.tokens.next())?;
         self.token_offset += 1;
-        self.string_byte_offset = result.byte_length();
+        self.byte_offset = result.byte_end();
         Some(result)
     }
 }
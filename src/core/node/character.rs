@@ -0,0 +1,55 @@
+use thiserror::Error;
+use crate::core::node;
+use crate::core::node::{escape, ErrorKind, Parsable, Traverser};
+use crate::core::token::Kind;
+
+pub type Node = node::Node<char>;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Character literal is empty")]
+    Empty,
+    #[error("Character literal contains more than one character, use a string instead")]
+    MultipleCharacters,
+    #[error("Character literal is never closed, add a `'` before the end of the line")]
+    Unterminated,
+    #[error("Invalid escape in character literal")]
+    Escape(#[from] escape::Error)
+}
+
+impl<'a> Parsable<'a> for Node {
+    type Error = Error;
+
+    fn parse(tokens: &mut Traverser<'a>) -> Result<Self, node::Error<Self::Error>> {
+        let start = tokens.offset();
+        let _ = tokens.expect_token(&Kind::CharacterQuote)?;
+        let mut value = None;
+        let mut count = 0usize;
+
+        // the lexer reads the literal in string mode, so it is made of text and escapes.
+        loop {
+            let Some(peeked) = tokens.peek() else { return Err(tokens.new_other_error(Error::Unterminated)) };
+            match *peeked.kind() {
+                Kind::CharacterQuote => break,
+                Kind::StringText(text) => {
+                    value = value.or(text.chars().next());
+                    count += text.chars().count();
+                },
+                Kind::EscapeSequence(sequence) => {
+                    let character = escape::decode(sequence).map_err(|error| tokens.new_other_error(error.into()))?;
+                    value = value.or(Some(character));
+                    count += 1;
+                },
+                _ => return Err(tokens.new_other_error(Error::Unterminated))
+            }
+            let _ = tokens.next();
+        }
+
+        let _ = tokens.next();
+        match (value, count) {
+            (Some(character), 1) => tokens.end(start, character),
+            (None, _) => Err(node::Error::at(start, ErrorKind::Other(Error::Empty))),
+            _ => Err(node::Error::at(start, ErrorKind::Other(Error::MultipleCharacters)))
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, Error, PartialEq)]
+pub enum Error {
+    #[error("Unknown escape `\\{0}`")]
+    Unknown(char),
+    #[error("Expected a character after `\\`")]
+    Incomplete,
+    #[error("Expected two hexadecimal digits after `\\x`")]
+    ExpectedHexadecimalByte,
+    #[error("Expected hexadecimal digits between braces after `\\u`, as in `\\u{{1F600}}`")]
+    MalformedUnicode,
+    #[error("U+{0:X} is not a Unicode scalar value")]
+    InvalidCodePoint(u32)
+}
+
+/// Decode an escape sequence as lexed by `token::Kind::EscapeSequence`, including its backslash.
+/// `\xNN` escapes select the codepoints U+0000 to U+00FF.
+pub fn decode(sequence: &str) -> Result<char, Error> {
+    let mut characters = sequence.strip_prefix('\\').unwrap_or(sequence).chars();
+    let escaped = characters.next().ok_or(Error::Incomplete)?;
+    let code = characters.as_str();
+
+    Ok(match escaped {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' | '"' | '\'' | '[' => escaped,
+        'x' => {
+            if code.len() != 2 { return Err(Error::ExpectedHexadecimalByte) }
+            char::from(u8::from_str_radix(code, 16).map_err(|_| Error::ExpectedHexadecimalByte)?)
+        },
+        'u' => {
+            let digits = code.strip_prefix('{').and_then(|code| code.strip_suffix('}')).ok_or(Error::MalformedUnicode)?;
+            if digits.is_empty() || digits.len() > 6 { return Err(Error::MalformedUnicode) }
+            let value = u32::from_str_radix(digits, 16).map_err(|_| Error::MalformedUnicode)?;
+            char::from_u32(value).ok_or(Error::InvalidCodePoint(value))?
+        },
+        _ => return Err(Error::Unknown(escaped))
+    })
+}
@@ -1,11 +1,34 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use thiserror::Error;
-use crate::core::node;
+use crate::core::{node, symbol};
 use crate::core::node::{ErrorKind, NodeVariant, Parsable, Traverser};
-use crate::core::token::Kind;
+use crate::core::symbol::Symbol;
+use crate::core::token::{Keyword, Kind};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Identifier<'a>(pub &'a str);
+/// A name as written in the source along with its interned symbol. Identifiers compare and hash by
+/// their symbol, so the same name typed in editors that normalise differently is still the same name.
+/// Only identifiers parsed with a shared [`symbol::Interner`] compare equal.
+#[derive(Debug, Clone, Copy)]
+pub struct Identifier<'a>(pub &'a str, pub Symbol);
+
+impl<'a> Identifier<'a> {
+    pub const fn symbol(&self) -> Symbol { self.1 }
+
+    /// The NFC normalised name, borrowed from the source when it is already normalised.
+    pub fn normalized(&self) -> Cow<'a, str> { symbol::normalize(self.0) }
+}
+
+impl PartialEq for Identifier<'_> {
+    fn eq(&self, other: &Self) -> bool { self.1 == other.1 }
+}
+
+impl Eq for Identifier<'_> {}
+
+impl Hash for Identifier<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.1.hash(state) }
+}
 
 pub type Node<'a> = node::Node<Identifier<'a>>;
 
@@ -14,18 +37,34 @@ pub enum Error {
     #[error("Expected an identifier")]
     ExpectedIdentifier,
     #[error("Expected an identifier but reached the end instead")]
-    ReachedIdentifierEnd
+    ReachedIdentifierEnd,
+    #[error("`{0}` is a reserved keyword, escape it as `_{0}` to use it as a name")]
+    ReservedKeyword(Keyword)
 }
 
 impl<'a> Node<'a> {
     fn next_identifier(tokens: &mut Traverser<'a>) -> Result<&'a str, node::Error<Error>> {
         let Some(peeked) = tokens.peek() else { return Err(tokens.new_other_error(Error::ReachedIdentifierEnd)) };
-        if let Kind::Identifier(identifier) = *peeked.kind() {
-            let  _ = tokens.next().unwrap();
-            return Ok(identifier);
+        match *peeked.kind() {
+            Kind::Identifier(identifier) => {
+                let _ = tokens.next().unwrap();
+                Ok(identifier)
+            },
+            Kind::Keyword(keyword) => Err(tokens.new_other_error(Error::ReservedKeyword(keyword))),
+            _ => Err(tokens.new_other_error(Error::ExpectedIdentifier))
         }
+    }
 
-        Err(tokens.new_other_error(Error::ExpectedIdentifier))
+    /// Parse the name after a `_` escape, which may be a keyword.
+    fn next_escaped_identifier(tokens: &mut Traverser<'a>) -> Result<&'a str, node::Error<Error>> {
+        let Some(peeked) = tokens.peek() else { return Err(tokens.new_other_error(Error::ReachedIdentifierEnd)) };
+        match *peeked.kind() {
+            Kind::Identifier(_) | Kind::Keyword(_) => {
+                let token = tokens.next().unwrap();
+                Ok(&tokens.source()[token.byte_range()])
+            },
+            _ => Err(tokens.new_other_error(Error::ExpectedIdentifier))
+        }
     }
 }
 
@@ -33,8 +72,12 @@ impl<'a> Parsable<'a> for Node<'a> {
     type Error = Error;
 
     fn parse(tokens: &mut Traverser<'a>) -> Result<Self, node::Error<Self::Error>> {
-        let start = tokens.token_offset();
-        let identifier = Self::next_identifier(tokens)?;
-        tokens.end(start, Identifier(identifier))
+        let start = tokens.offset();
+        let identifier = match tokens.skip_token(&Kind::IdentifierEscape) {
+            Some(_) => Self::next_escaped_identifier(tokens)?,
+            None => Self::next_identifier(tokens)?
+        };
+        let symbol = tokens.interner().intern(identifier);
+        tokens.end(start, Identifier(identifier, symbol))
     } 
-}
\ No newline at end of file
+}
@@ -1,15 +1,179 @@
 use std::fmt::Debug;
-use std::ops::{Add, Neg};
+use std::ops::Neg;
 use thiserror::Error;
 use crate::core::{node, token};
 use crate::core::node::{ErrorKind, NodeVariant, Parsable, Traverser};
-use crate::core::token::Kind;
+use crate::core::node::r#type::{Numeric, NumericKind};
+use crate::core::token::{Dialect, Kind, NumberLiteral, Radix};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Number {
-    UnSigned(u64),
-    Signed(i64),
-    Float(f64)
+/// An integer of any width, stored as its sign and little-endian 32-bit limbs of its magnitude.
+/// Literals too wide for 128 bits are kept in this form until a target scale is known.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Integer {
+    is_negative: bool,
+    magnitude: Vec<u32>
+}
+
+impl Integer {
+    pub const fn is_negative(&self) -> bool { self.is_negative }
+    pub fn magnitude(&self) -> &[u32] { &self.magnitude }
+
+    pub fn from_digits(digits: &str, radix: Radix) -> Result<Self, Error> {
+        let base = radix.base() as u64;
+        let mut magnitude = Vec::new();
+
+        for character in digits.chars().filter(|&character| character != '_') {
+            let mut carry = character.to_digit(radix.base()).ok_or(Error::InvalidDigit(character))? as u64;
+            for limb in magnitude.iter_mut() {
+                let product = *limb as u64 * base + carry;
+                *limb = product as u32;
+                carry = product >> u32::BITS;
+            }
+            if carry != 0 { magnitude.push(carry as u32) }
+        }
+
+        Ok(Self { is_negative: false, magnitude })
+    }
+
+    pub fn bit_length(&self) -> u32 {
+        match self.magnitude.last() {
+            Some(last) => (self.magnitude.len() as u32 - 1) * u32::BITS + (u32::BITS - last.leading_zeros()),
+            None => 0
+        }
+    }
+
+    fn is_power_of_two(&self) -> bool {
+        let Some((last, rest)) = self.magnitude.split_last() else { return false };
+        last.is_power_of_two() && rest.iter().all(|&limb| limb == 0)
+    }
+
+    fn magnitude_u128(&self) -> Option<u128> {
+        if self.bit_length() > u128::BITS { return None }
+        Some(self.magnitude.iter().rev().fold(0u128, |value, &limb| value << u32::BITS | limb as u128))
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.is_negative { return None }
+        self.magnitude_u128()
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude_u128()?;
+        if self.is_negative {
+            if magnitude > i128::MIN.unsigned_abs() { return None }
+            return Some((magnitude as i128).wrapping_neg());
+        }
+        i128::try_from(magnitude).ok()
+    }
+
+    /// Whether the integer is in range of an integer type with the given number of bits.
+    pub fn fits(&self, kind: NumericKind, bits: u32) -> bool {
+        let length = self.bit_length();
+        match kind {
+            NumericKind::Unsigned => !self.is_negative && length <= bits,
+            NumericKind::Integer if self.is_negative => length < bits || (length == bits && self.is_power_of_two()),
+            NumericKind::Integer => length < bits,
+            NumericKind::Float => false
+        }
+    }
+}
+
+impl Neg for Integer {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        let is_negative = !self.is_negative && !self.magnitude.is_empty();
+        Self { is_negative, magnitude: self.magnitude }
+    }
+}
+
+impl From<u128> for Integer {
+    fn from(value: u128) -> Self {
+        let magnitude = (0..u128::BITS / u32::BITS)
+            .map(|limb| (value >> (limb * u32::BITS)) as u32)
+            .collect::<Vec<_>>();
+        let length = magnitude.iter().rposition(|&limb| limb != 0).map_or(0, |last| last + 1);
+        Self { is_negative: false, magnitude: magnitude[..length].to_vec() }
+    }
+}
+
+impl From<i128> for Integer {
+    fn from(value: i128) -> Self {
+        let integer = Self::from(value.unsigned_abs());
+        if value < 0 { -integer } else { integer }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    UnSigned(u128),
+    Signed(i128),
+    Float(f64),
+    /// An integer literal whose target scale is not known yet, at its full width.
+    Integer(Integer)
+}
+
+impl Value {
+    /// Whether the value is in range of a numeric type.
+    pub fn fits(&self, numeric: Numeric) -> bool {
+        let bits = numeric.scale.bits();
+        match self {
+            Value::UnSigned(value) => Integer::from(*value).fits(numeric.kind, bits),
+            Value::Signed(value) => Integer::from(*value).fits(numeric.kind, bits),
+            Value::Float(value) => numeric.kind == NumericKind::Float && value.abs() <= numeric.float_max(),
+            Value::Integer(integer) => integer.fits(numeric.kind, bits)
+        }
+    }
+
+    /// Narrow a value once the type it is stored as is known. Integers become `Signed` or `UnSigned`
+    /// by the kind of the type, floats are returned as they are.
+    pub fn narrow(&self, numeric: Numeric) -> Result<Self, Error> {
+        if !self.fits(numeric) { return Err(Error::OverflowingScale) }
+        let integer = match self {
+            Value::UnSigned(value) => Integer::from(*value),
+            Value::Signed(value) => Integer::from(*value),
+            Value::Float(value) => return Ok(Value::Float(*value)),
+            Value::Integer(integer) => integer.clone()
+        };
+
+        let narrowed = match numeric.kind {
+            NumericKind::Unsigned => integer.to_u128().map(Value::UnSigned),
+            _ => integer.to_i128().map(Value::Signed)
+        };
+        narrowed.ok_or(Error::OverflowingScale)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Number {
+    pub value: Value,
+    /// The type requested by the literal's suffix, as in `255u1`.
+    pub numeric: Option<Numeric>
+}
+
+impl Number {
+    /// Write the number as a literal of a dialect, which parses back to the same number in that
+    /// dialect. Integers out of range of `i128` are written in hexadecimal.
+    pub fn to_source(&self, dialect: Dialect) -> String {
+        let mut source = match &self.value {
+            Value::UnSigned(value) => value.to_string(),
+            Value::Signed(value) => value.to_string(),
+            Value::Float(value) => {
+                let decimal = dialect.character(&Kind::Decimal).unwrap_or('.');
+                format!("{:?}", value).replace('.', decimal.encode_utf8(&mut [0; 4]))
+            },
+            Value::Integer(integer) => if let Some(value) = integer.to_i128() { value.to_string() } else {
+                let mut limbs = integer.magnitude().iter().rev();
+                let mut text = String::from(if integer.is_negative() { "-0x" } else { "0x" });
+                text.push_str(&format!("{:X}", limbs.next().copied().unwrap_or(0)));
+                text.extend(limbs.map(|limb| format!("{:08X}", limb)));
+                text
+            }
+        };
+
+        if let Some(numeric) = self.numeric { source.push_str(&numeric.suffix()) }
+        source
+    }
 }
 
 pub type Node = node::Node<Number>;
@@ -18,66 +182,169 @@ pub type Node = node::Node<Number>;
 pub enum Error {
     #[error("The number is too large to be stored as a literal")]
     OverflowingWhole,
-    #[error("The fractional component is too large to be stored as a literal")]
-    OverflowingFractional,
     #[error("Expected whole number")]
     ExpectedWholeNumberComponent,
     #[error("Expected fractional number after decimal separator")]
-    ExpectedFractionalNumberComponent
+    ExpectedFractionalNumberComponent,
+    #[error("The character `{0}` is not a digit in the literal's radix")]
+    InvalidDigit(char),
+    #[error("Only decimal numbers can have a fractional component")]
+    NonDecimalFractional,
+    #[error("Expected exponent digits after `e`")]
+    ExpectedExponent,
+    #[error("The exponent is too large to be stored as a literal")]
+    OverflowingExponent,
+    #[error("Unknown number suffix")]
+    InvalidSuffix,
+    #[error("A fractional number cannot have an integer suffix")]
+    FractionalSuffix,
+    #[error("The number does not fit in the type selected by its suffix")]
+    OverflowingSuffix,
+    #[error("The number does not fit in the type it is narrowed to")]
+    OverflowingScale,
+    #[error("The number is too large to be stored as a float")]
+    OverflowingFloat
+}
+
+/// The parts of a literal that may follow its digits.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Suffix {
+    exponent: Option<i32>,
+    numeric: Option<Numeric>
+}
+
+impl Suffix {
+    const fn is_empty(self) -> bool { self.exponent.is_none() && self.numeric.is_none() }
 }
 
 impl<'a> Node {
-    fn next_digit(tokens: &mut Traverser<'a>) -> Option<u8> {
-        let peeked = tokens.peek()?;
-        if let Kind::Digit(digit) = *peeked.kind() {
-            let  _ = tokens.next()?;
-            return Some(digit);
+    fn peek_literal(tokens: &mut Traverser<'a>) -> Option<NumberLiteral<'a>> {
+        match *tokens.peek()?.kind() {
+            Kind::Number(literal) => Some(literal),
+            _ => None
         }
+    }
+
+    /// Split a literal into its digits, the text of its exponent after the `e` and its type suffix.
+    /// Exponents are only recognised on decimal literals and `f` is a hexadecimal digit, so neither
+    /// can be written on a hexadecimal literal.
+    fn split(literal: &NumberLiteral<'a>) -> (&'a str, Option<&'a str>, Option<&'a str>) {
+        let suffix_starts: &[char] = match literal.radix {
+            Radix::Hexadecimal => &['i', 'u'],
+            _ => &['i', 'u', 'f']
+        };
+        let (rest, suffix) = match literal.digits().find(suffix_starts) {
+            Some(index) => (&literal.digits()[..index], Some(&literal.digits()[index..])),
+            None => (literal.digits(), None)
+        };
 
-        None
+        if literal.radix != Radix::Decimal { return (rest, None, suffix) }
+        match rest.split_once(['e', 'E']) {
+            Some((digits, exponent)) => (digits, Some(exponent), suffix),
+            None => (rest, None, suffix)
+        }
     }
 
-    fn next_number(tokens: &mut Traverser<'a>) -> Result<u64, node::Error<<Self as Parsable<'a>>::Error>> {
-        let mut value = 0u64;
-        let mut power = 0u32;
+    fn validate_digits(digits: &str, radix: Radix, missing: Error) -> Result<(), Error> {
+        let mut digits = digits.chars().filter(|&character| character != '_').peekable();
+        if digits.peek().is_none() { return Err(missing) }
+        
+        match digits.find(|character| !character.is_digit(radix.base())) {
+            Some(character) => Err(Error::InvalidDigit(character)),
+            None => Ok(())
+        }
+    }
 
-        while let Some(digit) = Self::next_digit(tokens) {
-            let digit_offset = 10u64
-                .checked_pow(power)
-                .ok_or(tokens.new_other_error(Error::OverflowingWhole))?
-                .checked_mul(digit as u64)
-                .ok_or(tokens.new_other_error(Error::OverflowingWhole))?;
-            value = value
-                .checked_add(digit_offset)
-                .ok_or(tokens.new_other_error(Error::OverflowingWhole))?;
+    /// Parse a decimal float from its digit strings. The digits are handed to the standard library's
+    /// parser so that the result is correctly rounded, exactly as `str::parse::<f64>` would give.
+    fn float_value(whole: &str, fractional: Option<&str>, exponent: Option<i32>) -> Result<f64, Error> {
+        let mut text = String::with_capacity(whole.len() + fractional.map_or(0, str::len) + 12);
+        text.extend(whole.chars().filter(|&character| character != '_'));
+        
+        if let Some(fractional) = fractional {
+            text.push('.');
+            text.extend(fractional.chars().filter(|&character| character != '_'));
+        }
+        if let Some(exponent) = exponent {
+            text.push('e');
+            text.push_str(&exponent.to_string());
+        }
+
+        let float = text.parse::<f64>().map_err(|_| Error::OverflowingFloat)?;
+        if float.is_infinite() { return Err(Error::OverflowingFloat) }
+        Ok(float)
+    }
+
+    fn exponent_value(exponent: &str) -> Result<i32, Error> {
+        let (is_negative, digits) = match exponent.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, exponent.strip_prefix('+').unwrap_or(exponent))
+        };
+        let mut value = 0i32;
+        let mut count = 0usize;
 
-            power = power
-                .checked_add(1)
-                .ok_or(tokens.new_other_error(Error::OverflowingWhole))?;
+        for character in digits.chars().filter(|&character| character != '_') {
+            let digit = character.to_digit(10).ok_or(Error::InvalidDigit(character))?;
+            value = value
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(digit as i32))
+                .ok_or(Error::OverflowingExponent)?;
+            count += 1;
         }
 
-        if power == 0 { return Err(tokens.new_other_error(Error::ExpectedWholeNumberComponent)) }
-        Ok(value)
+        if count == 0 { return Err(Error::ExpectedExponent) }
+        Ok(if is_negative { -value } else { value })
+    }
+
+    fn suffix_value(exponent: Option<&str>, numeric: Option<&str>) -> Result<Suffix, Error> {
+        Ok(Suffix {
+            exponent: exponent.map(Self::exponent_value).transpose()?,
+            numeric: numeric.map(|suffix| Numeric::from_suffix(suffix).ok_or(Error::InvalidSuffix)).transpose()?
+        })
+    }
+
+    fn next_number(tokens: &mut Traverser<'a>) -> Result<(&'a str, NumberLiteral<'a>, Suffix), node::Error<<Self as Parsable<'a>>::Error>> {
+        let literal = Self::peek_literal(tokens).ok_or(tokens.new_other_error(Error::ExpectedWholeNumberComponent))?;
+        let (digits, exponent, numeric) = Self::split(&literal);
+
+        // the literal is only consumed once it is known to be valid so that errors point at it.
+        Self::validate_digits(digits, literal.radix, Error::ExpectedWholeNumberComponent).map_err(|error| tokens.new_other_error(error))?;
+        let suffix = Self::suffix_value(exponent, numeric).map_err(|error| tokens.new_other_error(error))?;
+
+        let _ = tokens.next();
+        Ok((digits, literal, suffix))
     }
 
-    fn next_decimal(tokens: &mut Traverser<'a>) -> Result<f64, node::Error<<Self as Parsable<'a>>::Error>> {
-        let mut accumulator = 0f64;
-        let mut division = 0i32;
+    fn next_decimal(tokens: &mut Traverser<'a>) -> Result<(&'a str, Suffix), node::Error<<Self as Parsable<'a>>::Error>> {
+        let literal = Self::peek_literal(tokens).ok_or(tokens.new_other_error(Error::ExpectedFractionalNumberComponent))?;
+        if literal.radix != Radix::Decimal { return Err(tokens.new_other_error(Error::NonDecimalFractional)) }
 
-        while let Some(digit) = Self::next_digit(tokens) {
-            // adding to the divisor is done first so that we can check to see if digits were read
-            // by testing whether the division is not zero.
-            division = division
-                .checked_add(1)
-                .ok_or(tokens.new_other_error(Error::OverflowingFractional))?;
+        let (digits, exponent, numeric) = Self::split(&literal);
+        Self::validate_digits(digits, literal.radix, Error::ExpectedFractionalNumberComponent).map_err(|error| tokens.new_other_error(error))?;
+        let suffix = Self::suffix_value(exponent, numeric).map_err(|error| tokens.new_other_error(error))?;
+
+        let _ = tokens.next();
+        Ok((digits, suffix))
+    }
 
-            // fixme: Floats do not overflow, but we need to wash out numbers that are wastefully
-            // fixme: large and are infinity.
-            accumulator = accumulator.add(digit as f64 / 10f64.powi(division));
+    fn value(whole: &str, radix: Radix, fractional: Option<&str>, is_negative: bool, suffix: Suffix) -> Result<Value, Error> {
+        let is_float = fractional.is_some()
+            || suffix.exponent.is_some()
+            || suffix.numeric.is_some_and(|numeric| numeric.kind == NumericKind::Float);
+
+        if is_float {
+            let float = match radix {
+                Radix::Decimal => Self::float_value(whole, fractional, suffix.exponent)?,
+                // integers convert to the nearest float, so this is correctly rounded as well.
+                _ => Integer::from_digits(whole, radix)?.to_u128().ok_or(Error::OverflowingWhole)? as f64
+            };
+            return Ok(Value::Float(if is_negative { float.neg() } else { float }));
         }
 
-        if division == 0 { return Err(tokens.new_other_error(Error::ExpectedFractionalNumberComponent)) }
-        Ok(accumulator)
+        // integers keep their full width until they are narrowed, so negating them first lets the
+        // smallest value of each signed width be written.
+        let integer = Integer::from_digits(whole, radix)?;
+        Ok(Value::Integer(if is_negative { -integer } else { integer }))
     }
 }
 
@@ -85,23 +352,33 @@ impl<'a> Parsable<'a> for Node {
     type Error = Error;
 
     fn parse(tokens: &mut Traverser<'a>) -> Result<Self, node::Error<Self::Error>> {
-        let start = tokens.token_offset();
+        let start = tokens.offset();
         let is_negative = tokens.skip_token(&token::Kind::Negate).is_some();
-        let whole = Self::next_number(tokens)?;
-        let is_fractional = tokens.skip_token(&token::Kind::Decimal).is_some();
+        let (whole, literal, mut suffix) = Self::next_number(tokens)?;
+        let is_fractional = suffix.is_empty() && tokens.skip_token(&token::Kind::Decimal).is_some();
+        let mut fractional = None;
 
         if is_fractional {
-            let mut fractional = Self::next_decimal(tokens)?;
-            fractional += whole as f64; // fixme: check for data inconsistency potential.
-            if is_negative { fractional = fractional.neg() }
-            return tokens.end(start, Number::Float(fractional));
+            if literal.radix != Radix::Decimal { return Err(tokens.new_other_error(Error::NonDecimalFractional)) }
+
+            let (digits, fraction_suffix) = Self::next_decimal(tokens)?;
+            fractional = Some(digits);
+            suffix = fraction_suffix;
         }
 
-        if is_negative {
-            let negated = -i64::try_from(whole).map_err(|_| tokens.new_other_error(Error::OverflowingWhole))?;
-            return tokens.end(start, Number::Signed(negated));
+        // errors about the value as a whole point at the start of the number.
+        let mut value = Self::value(whole, literal.radix, fractional, is_negative, suffix)
+            .map_err(|error| node::Error::at(start, ErrorKind::Other(error)))?;
+        
+        if let Some(numeric) = suffix.numeric {
+            let is_fractional_integer = matches!(value, Value::Float(_)) && numeric.kind != NumericKind::Float;
+            if is_fractional_integer { return Err(node::Error::at(start, ErrorKind::Other(Error::FractionalSuffix))) }
+            if !value.fits(numeric) { return Err(node::Error::at(start, ErrorKind::Other(Error::OverflowingSuffix))) }
+
+            // the suffix gives the scale, so the value does not need to be kept at full width.
+            value = value.narrow(numeric).map_err(|error| node::Error::at(start, ErrorKind::Other(error)))?;
         }
 
-        tokens.end(start, Number::UnSigned(whole))
+        tokens.end(start, Number { value, numeric: suffix.numeric })
     }
-}
\ No newline at end of file
+}
@@ -1,43 +1,245 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use thiserror::Error;
 use crate::core::node;
-use crate::core::node::{NodeVariant, Parsable, Traverser};
-use crate::core::token::Kind;
+use crate::core::node::{character, escape, identifier, number, whitespace, ErrorKind, NodeVariant, Parsable, Traverser};
+use crate::core::token::{Kind, StringForm};
 use crate::Pbt;
 
-pub type Node<'a> = node::Node<&'a str>;
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    /// Text with escapes decoded and, in multi-line strings, the shared indentation removed.
+    Text(Cow<'a, str>),
+    /// An expression written between `[` and `]`, whose value is inserted into the string.
+    Interpolation(NodeVariant<'a>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal<'a> {
+    /// The contents as written between the delimiters.
+    pub raw: &'a str,
+    pub segments: Vec<Segment<'a>>
+}
+
+impl<'a> Literal<'a> {
+    /// The text of a string that has no interpolations.
+    pub fn text(&self) -> Option<&str> {
+        match self.segments.as_slice() {
+            [] => Some(""),
+            [Segment::Text(text)] => Some(text),
+            _ => None
+        }
+    }
+}
+
+pub type Node<'a> = node::Node<Literal<'a>>;
+
+/// A part of a string's contents. Parts are kept until the whole string has been read, since the
+/// indentation removed from a multi-line string depends on its last line.
+enum Part<'a> {
+    Text { text: &'a str, byte_start: usize },
+    Escape(char),
+    Interpolation { expression: NodeVariant<'a>, byte_range: Range<usize> }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Invalid escape `{sequence}` in string")]
+    InvalidEscape { sequence: String, error: escape::Error },
+    #[error("Expected an expression after `[`, write `\\[` for a literal bracket")]
+    ExpectedExpression,
+    #[error("Expected `]` to close the interpolation")]
+    UnclosedInterpolation,
+    #[error("String literal is never closed, add its closing quote")]
+    Unterminated
+}
+
+impl<'a> Node<'a> {
+    /// Split text into lines, along with the offset of each line and the line ending that follows
+    /// it. The last line has an empty ending.
+    fn lines(text: &str) -> impl Iterator<Item = (usize, &str, &str)> {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            if offset > text.len() { return None }
+            let rest = &text[offset..];
+            let line_offset = offset;
+            let Some(end) = rest.find(['\n', '\r']) else {
+                offset = text.len() + 1;
+                return Some((line_offset, rest, ""));
+            };
+
+            let ending = if rest[end..].starts_with("\r\n") { "\r\n".len() } else { 1 };
+            offset += end + ending;
+            Some((line_offset, &rest[..end], &rest[end..end + ending]))
+        })
+    }
+
+    /// Find the byte ranges of a multi-line string's contents that are not part of its value: the line
+    /// break after the opening `"""` and the indentation its lines have in common. When the closing
+    /// `"""` is on a line of its own, its indentation counts too and the line is not part of the
+    /// value.
+    fn removed_ranges(contents: &str, byte_start: usize) -> Vec<Range<usize>> {
+        let Some(opening) = ["\r\n", "\n", "\r"].into_iter().find(|ending| contents.starts_with(ending)) else { return Vec::new() };
+        let body = &contents[opening.len()..];
+        let body_start = byte_start + opening.len();
+
+        let indentation = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+        let mut lines = Self::lines(body).collect::<Vec<_>>();
+        let closing = match lines.last() {
+            Some(&(_, last, _)) if indentation(last) == last.len() => lines.pop().map(|(_, last, _)| last.len()),
+            _ => None
+        };
+
+        let mut removed = Vec::new();
+        if closing.is_some() {
+            let closing_start = lines.last().map_or(0, |&(offset, line, _)| offset + line.len());
+            removed.push(body_start + closing_start..body_start + body.len());
+        }
+
+        let common = lines
+            .iter()
+            .filter(|(_, line, _)| indentation(line) != line.len())
+            .map(|(_, line, _)| indentation(line))
+            .chain(closing)
+            .min()
+            .unwrap_or(0);
+
+        for (offset, line, _) in lines {
+            let length = common.min(indentation(line));
+            if length != 0 { removed.push(body_start + offset..body_start + offset + length) }
+        }
+
+        removed.push(byte_start..body_start);
+        removed.sort_by_key(|range| range.start);
+        removed
+    }
+
+    /// Append the text of a token starting at `byte_start`, leaving out the removed ranges.
+    fn push_text(value: &mut String, text: &str, byte_start: usize, removed: &[Range<usize>]) {
+        let byte_end = byte_start + text.len();
+        let mut position = byte_start;
+
+        for range in removed.iter().filter(|range| range.start < byte_end && range.end > byte_start) {
+            if range.start > position { value.push_str(&text[position - byte_start..range.start - byte_start]) }
+            position = position.max(range.end);
+        }
+
+        if position < byte_end { value.push_str(&text[position - byte_start..]) }
+    }
+
+    /// Add the text read since the last interpolation as a segment. Text without escapes or removed
+    /// ranges is borrowed from the source.
+    fn push_segment(segments: &mut Vec<Segment<'a>>, text: &mut String, decoded: &mut bool, raw: &'a str) {
+        let text = std::mem::take(text);
+        let segment = if std::mem::take(decoded) { Cow::Owned(text) } else { Cow::Borrowed(raw) };
+        if !segment.is_empty() { segments.push(Segment::Text(segment)) }
+    }
+
+    /// Parse an interpolation starting at its `[`. The expression may be surrounded by whitespace.
+    fn next_interpolation(traverser: &mut Traverser<'a>) -> Result<NodeVariant<'a>, node::Error<Error>> {
+        let _ = traverser.expect_token::<Error>(&Kind::InterpolationStart)?;
+        let _ = traverser.as_restorable(whitespace::Node::parse);
+
+        let expression = if let Ok(string) = traverser.as_restorable(Self::parse) {
+            NodeVariant::String(string)
+        } else if let Ok(character) = traverser.as_restorable(character::Node::parse) {
+            NodeVariant::Character(character)
+        } else if let Ok(number) = traverser.as_restorable(number::Node::parse) {
+            NodeVariant::Number(number)
+        } else if let Ok(identifier) = traverser.as_restorable(identifier::Node::parse) {
+            NodeVariant::Identifier(identifier)
+        } else {
+            return Err(traverser.new_other_error(Error::ExpectedExpression));
+        };
+
+        let _ = traverser.as_restorable(whitespace::Node::parse);
+        traverser.expect_token::<Error>(&Kind::InterpolationEnd).map_err(|_| traverser.new_other_error(Error::UnclosedInterpolation))?;
+        Ok(expression)
+    }
+
+    /// Join the parts of the contents between `byte_start` and `byte_end` into segments, leaving out
+    /// the removed ranges.
+    fn segments(parts: Vec<Part<'a>>, removed: &[Range<usize>], source: &'a str, byte_start: usize, byte_end: usize) -> Vec<Segment<'a>> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut text_start = byte_start;
+        let mut decoded = false;
+
+        for part in parts {
+            match part {
+                Part::Text { text: value, byte_start } => {
+                    Self::push_text(&mut text, value, byte_start, removed);
+                    decoded |= !removed.is_empty();
+                },
+                Part::Escape(character) => {
+                    text.push(character);
+                    decoded = true;
+                },
+                Part::Interpolation { expression, byte_range } => {
+                    Self::push_segment(&mut segments, &mut text, &mut decoded, &source[text_start..byte_range.start]);
+                    segments.push(Segment::Interpolation(expression));
+                    text_start = byte_range.end;
+                }
+            }
+        }
+
+        Self::push_segment(&mut segments, &mut text, &mut decoded, &source[text_start..byte_end]);
+        segments
+    }
+}
 
 impl<'a> Parsable<'a> for Node<'a> {
-    type Error = ();
+    type Error = Error;
 
-    fn parse(traverser: &mut Traverser<'a>) -> Result<Self, node::Error<()>> {
-        let start = traverser.token_offset();
-        let _ = traverser.expect_token(&Kind::StringQuote)?;
+    fn parse(traverser: &mut Traverser<'a>) -> Result<Self, node::Error<Self::Error>> {
+        let start = traverser.offset();
+        let Some(peeked) = traverser.peek() else { return Err(traverser.new_error(ErrorKind::ReachedEndForToken)) };
+        let form = match *peeked.kind() {
+            Kind::RawString(string) => {
+                if !string.is_terminated() { return Err(traverser.new_other_error(Error::Unterminated)) }
+                let _ = traverser.next();
+                let segments = if string.content.is_empty() { Vec::new() } else { vec![Segment::Text(Cow::Borrowed(string.content))] };
+                return traverser.end(start, Literal { raw: string.content, segments });
+            },
+            Kind::StringQuote => StringForm::Quoted,
+            Kind::MultiLineQuote => StringForm::MultiLine,
+            _ => return Err(traverser.new_error(ErrorKind::UnexpectedToken))
+        };
+
+        let _ = traverser.next();
         let byte_start = traverser.byte_offset();
-        let mut byte_end = byte_start;
-        let mut escaping = false;
-
-        while let Some(peeked) = traverser.peek() {
-            match peeked.kind() {
-                Kind::Escape => {
-                    if escaping { byte_end += '\\'.len_utf8() }
-                    escaping = !escaping;
+        let mut parts = Vec::new();
+
+        // the lexer is in string mode until the closing quote, so the contents are text, escapes and
+        // interpolations.
+        loop {
+            let Some(&token) = traverser.peek() else { return Err(traverser.new_other_error(Error::Unterminated)) };
+            match *token.kind() {
+                kind if kind == form.quote_kind() => break,
+                Kind::EscapeSequence(sequence) => {
+                    let character = escape::decode(sequence).map_err(|error| {
+                        traverser.new_other_error(Error::InvalidEscape { sequence: sequence.to_owned(), error })
+                    })?;
+                    parts.push(Part::Escape(character));
                 },
-                Kind::StringQuote => {
-                    if !escaping { 
-                        let _ = traverser.next();
-                        break
-                    }
-                    escaping = false;
-                    byte_end += '"'.len_utf8();
+                Kind::StringText(text) => parts.push(Part::Text { text, byte_start: token.byte_start() }),
+                Kind::InterpolationStart => {
+                    let expression = Self::next_interpolation(traverser)?;
+                    parts.push(Part::Interpolation { expression, byte_range: token.byte_start()..traverser.byte_offset() });
+                    continue;
                 },
-                _ => {
-                    if escaping { return Err(traverser.new_other_error(())) }
-                    byte_end = peeked.byte_length()
-                }
+                _ => return Err(traverser.new_error(ErrorKind::UnexpectedToken))
             }
-            
+
             let _ = traverser.next();
         }
 
-        traverser.end(start, &traverser.source()[byte_start..byte_end])
+        let byte_end = traverser.byte_offset();
+        let _ = traverser.next();
+
+        let raw = &traverser.source()[byte_start..byte_end];
+        let removed = if form == StringForm::MultiLine { Self::removed_ranges(raw, byte_start) } else { Vec::new() };
+        let segments = Self::segments(parts, &removed, traverser.source(), byte_start, byte_end);
+        traverser.end(start, Literal { raw, segments })
     }
-}
\ No newline at end of file
+}
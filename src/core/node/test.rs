@@ -1,8 +1,265 @@
-use crate::core::node::Traverser;
-use crate::core::node::whitespace::Node;
+use std::borrow::Cow;
+use crate::core::node::{character, escape, identifier, number, r#type, string, ErrorKind, NodeVariant, Parsable, Traverser};
+use crate::core::node::number::{Integer, Value};
+use crate::core::node::r#type::{MainType, Numeric, NumericKind, Scale};
+use crate::core::node::string::Segment;
+use crate::core::node::whitespace::{Node, WhiteSpace};
+use crate::core::symbol::Interner;
+use crate::core::token;
+use crate::core::token::{Dialect, Keyword, LineEnding};
 
 #[test]
 fn expect_token() {
     let mut traverser = Traverser::from("\n\n\t");
     dbg!(traverser.expect_tokens::<()>(&Node::WHITESPACE_TOKENS));
-}
\ No newline at end of file
+}
+
+#[test]
+fn byte_range() {
+    let source = "\t \"text\" 14";
+    let mut traverser = Traverser::from_str(source);
+    let _ = Node::parse(&mut traverser).unwrap();
+    let string = string::Node::parse(&mut traverser).unwrap();
+    assert_eq!(&source[string.byte_range()], "\"text\"");
+    assert_eq!(string.data().text(), Some("text"));
+}
+
+#[test]
+fn line_endings() {
+    let mut traverser = Traverser::from_str("\r\n\n\r");
+    let whitespace = Node::parse(&mut traverser).unwrap();
+    assert_eq!(whitespace.data().as_ref(), [
+        WhiteSpace::NewLine(LineEnding::CarriageReturnLineFeed),
+        WhiteSpace::NewLine(LineEnding::LineFeed),
+        WhiteSpace::NewLine(LineEnding::CarriageReturn)
+    ]);
+}
+
+#[test]
+fn number() {
+    let mut traverser = Traverser::from_str("-3383888888");
+    let value = number::Node::parse(&mut traverser).unwrap().data().value.clone();
+    assert_eq!(value, Value::Integer(Integer::from(-3383888888i128)));
+    assert_eq!(value.narrow(Numeric { kind: NumericKind::Integer, scale: Scale::X64 }), Ok(Value::Signed(-3383888888)));
+    assert_eq!(value.narrow(Numeric { kind: NumericKind::Integer, scale: Scale::X32 }), Err(number::Error::OverflowingScale));
+    
+    let source = "10000000000000000000,5";
+    let mut traverser = Traverser::from_str(source);
+    let whole = number::Node::parse(&mut traverser).unwrap();
+    assert_eq!(whole.data().value, Value::Float(10000000000000000000.5));
+    assert_eq!(whole.byte_range(), 0..source.len());
+}
+
+#[test]
+fn number_radix() {
+    let mut traverser = Traverser::from_str("0xFFFF_0000 0b1010 -0o17 0b102");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Integer(Integer::from(0xFFFF_0000u128)));
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Integer(Integer::from(0b1010u128)));
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Integer(Integer::from(-0o17i128)));
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert_eq!(number::Node::parse(&mut traverser).unwrap_err().kind, ErrorKind::Other(number::Error::InvalidDigit('2')));
+}
+
+#[test]
+fn number_exponent() {
+    let mut traverser = Traverser::from_str("1,5e3");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Float(1500.0));
+    let mut traverser = Traverser::from_str("-15e-1");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Float(-1.5));
+    let mut traverser = Traverser::from_str("1,5e");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap_err().kind, ErrorKind::Other(number::Error::ExpectedExponent));
+}
+
+#[test]
+fn number_suffix() {
+    let mut traverser = Traverser::from_str("255u1");
+    let number = number::Node::parse(&mut traverser).unwrap();
+    assert_eq!(number.data().value, Value::UnSigned(255));
+    assert_eq!(number.data().numeric, Some(Numeric { kind: NumericKind::Unsigned, scale: Scale::X8 }));
+    
+    let mut traverser = Traverser::from_str("5i1");
+    let number = number::Node::parse(&mut traverser).unwrap();
+    assert_eq!(number.data().value, Value::Signed(5));
+    assert_eq!(number.data().numeric, Some(Numeric { kind: NumericKind::Integer, scale: Scale::X8 }));
+    
+    let mut traverser = Traverser::from_str("3,5f4");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Float(3.5));
+    let mut traverser = Traverser::from_str("-129i1");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap_err().kind, ErrorKind::Other(number::Error::OverflowingSuffix));
+}
+
+#[test]
+fn number_rounding() {
+    for source in ["6,022e23", "0,1", "2,2250738585072011e-308", "4,9e-324", "9007199254740993,0"] {
+        let mut traverser = Traverser::from_str(source);
+        let expected = source.replace(',', ".").parse::<f64>().unwrap();
+        let Value::Float(parsed) = number::Node::parse(&mut traverser).unwrap().data().value else { panic!() };
+        assert_eq!(parsed.to_bits(), expected.to_bits());
+    }
+    
+    let mut traverser = Traverser::from_str("1,8e308");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap_err().kind, ErrorKind::Other(number::Error::OverflowingFloat));
+}
+
+#[test]
+fn number_dialect() {
+    let sources = ["1,5", "-2,25e-9", "3,5f4", "255u1", "-170141183460469231731687303715884105728i16", "0x1_0000_0000_0000_0000_0000_0000_0000_0000"];
+    for dialect in [Dialect::DEFAULT, Dialect::DOT_DECIMAL] {
+        for source in sources {
+            let source = if dialect == Dialect::DOT_DECIMAL { source.replace(',', ".") } else { source.to_owned() };
+            let number = number::Node::parse(&mut Traverser::with_dialect(&source, Interner::default(), dialect)).unwrap();
+            let written = number.data().to_source(dialect);
+            let reparsed = number::Node::parse(&mut Traverser::with_dialect(&written, Interner::default(), dialect)).unwrap();
+            assert_eq!(reparsed.data(), number.data(), "{} was written as {}", source, written);
+        }
+    }
+    
+    let mut traverser = Traverser::with_dialect("1.5;", Interner::default(), Dialect::DOT_DECIMAL);
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Float(1.5));
+    assert_eq!(traverser.next().map(|token| *token.kind()), Some(token::Kind::Stop));
+}
+
+#[test]
+fn number_wide() {
+    let mut traverser = Traverser::from_str("-9223372036854775808i8");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Signed(i64::MIN as i128));
+    let mut traverser = Traverser::from_str("-170141183460469231731687303715884105728i16");
+    assert_eq!(number::Node::parse(&mut traverser).unwrap().data().value, Value::Signed(i128::MIN));
+    
+    let mut traverser = Traverser::from_str("0x1_0000_0000_0000_0000_0000_0000_0000_0000");
+    let Value::Integer(integer) = number::Node::parse(&mut traverser).unwrap().data().value.clone() else { panic!() };
+    assert_eq!(integer.bit_length(), 129);
+    assert!(!integer.fits(NumericKind::Unsigned, 128));
+    assert_eq!(Value::Integer(integer).narrow(Numeric { kind: NumericKind::Unsigned, scale: Scale::X128 }), Err(number::Error::OverflowingScale));
+    
+    let mut traverser = Traverser::from_str("255");
+    let value = number::Node::parse(&mut traverser).unwrap().data().value.clone();
+    assert_eq!(value.narrow(Numeric { kind: NumericKind::Unsigned, scale: Scale::X8 }), Ok(Value::UnSigned(255)));
+    assert_eq!(value.narrow(Numeric { kind: NumericKind::Integer, scale: Scale::X16 }), Ok(Value::Signed(255)));
+    assert_eq!(Value::Integer(Integer::from(-129i128)).narrow(Numeric { kind: NumericKind::Integer, scale: Scale::X8 }), Err(number::Error::OverflowingScale));
+}
+
+#[test]
+fn keyword() {
+    let mut traverser = Traverser::from_str("fun _fun integer8");
+    assert_eq!(identifier::Node::parse(&mut traverser).unwrap_err().kind, ErrorKind::Other(identifier::Error::ReservedKeyword(Keyword::Fun)));
+    let _ = traverser.next();
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert_eq!(identifier::Node::parse(&mut traverser).unwrap().data().0, "fun");
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert_eq!(r#type::Node::parse(&mut traverser).unwrap().data(), &MainType::Numeric(Numeric { kind: NumericKind::Integer, scale: Scale::X64 }));
+}
+
+#[test]
+fn identifier_normalization() {
+    let mut traverser = Traverser::from_str("caf\u{E9} cafe\u{301} \u{663}x");
+    let composed = identifier::Node::parse(&mut traverser).unwrap();
+    let _ = Node::parse(&mut traverser).unwrap();
+    let decomposed = identifier::Node::parse(&mut traverser).unwrap();
+    assert_eq!(composed.data(), decomposed.data());
+    assert_eq!(decomposed.data().normalized(), "caf\u{E9}");
+    
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert!(identifier::Node::parse(&mut traverser).is_err());
+    assert_eq!(traverser.diagnostics()[0].error, token::Error::InvalidIdentifierStart('\u{663}'));
+}
+
+#[test]
+fn shared_interner() {
+    let interner = Interner::default();
+    let first = identifier::Node::parse(&mut Traverser::with_interner("table", interner.clone())).unwrap();
+    let second = identifier::Node::parse(&mut Traverser::with_interner("table", interner.clone())).unwrap();
+    let other = identifier::Node::parse(&mut Traverser::with_interner("chair", interner.clone())).unwrap();
+    
+    assert_eq!(first.data().symbol(), second.data().symbol());
+    assert_ne!(first.data(), other.data());
+    assert_eq!(&*interner.resolve(other.data().symbol()), "chair");
+}
+
+#[test]
+fn separate_interners() {
+    let first = identifier::Node::parse(&mut Traverser::from_str("table")).unwrap();
+    let other = identifier::Node::parse(&mut Traverser::from_str("chair")).unwrap();
+    let same = identifier::Node::parse(&mut Traverser::from_str("table")).unwrap();
+    
+    // every traverser has its own interner, so its symbols are never confused with another's.
+    assert_eq!(first.data().symbol().index(), other.data().symbol().index());
+    assert_ne!(first.data(), other.data());
+    assert_ne!(first.data(), same.data());
+}
+
+#[test]
+fn raw_and_multi_line_string() {
+    let mut traverser = Traverser::from_str(r###"##"C:\path "#1"#"##"###);
+    assert_eq!(string::Node::parse(&mut traverser).unwrap().data().text(), Some(r##"C:\path "#1"#"##));
+    
+    let mut traverser = Traverser::from_str("\"\"\"\n    mov \"a\"\n\n      add \\[b]\n    \"\"\" x");
+    assert_eq!(string::Node::parse(&mut traverser).unwrap().data().text(), Some("mov \"a\"\n\n  add [b]"));
+    assert_eq!(traverser.next().map(|token| *token.kind()), Some(token::Kind::Space));
+    
+    let mut traverser = Traverser::from_str("\"\"\"\n    a [x]\n      b\n    \"\"\"");
+    let string = string::Node::parse(&mut traverser).unwrap();
+    let [Segment::Text(before), Segment::Interpolation(NodeVariant::Identifier(name)), Segment::Text(after)] = &string.data().segments[..] else {
+        panic!("unexpected segments {:?}", string.data().segments)
+    };
+    assert_eq!((before.as_ref(), name.data().0, after.as_ref()), ("a ", "x", "\n  b"));
+}
+
+#[test]
+fn character() {
+    let parse = |source| character::Node::parse(&mut Traverser::from_str(source)).map(|node| *node.data()).map_err(|error| error.kind);
+    assert_eq!(parse("'a'"), Ok('a'));
+    assert_eq!(parse("'['"), Ok('['));
+    assert_eq!(parse("'\\''"), Ok('\''));
+    assert_eq!(parse("'\\u{1F600}'"), Ok('\u{1F600}'));
+    assert_eq!(parse("'\\x41'"), Ok('A'));
+    assert_eq!(parse("''"), Err(ErrorKind::Other(character::Error::Empty)));
+    assert_eq!(parse("'ab'"), Err(ErrorKind::Other(character::Error::MultipleCharacters)));
+    assert_eq!(parse("'a\nb'"), Err(ErrorKind::Other(character::Error::Unterminated)));
+    assert_eq!(parse("'\\q'"), Err(ErrorKind::Other(character::Error::Escape(escape::Error::Unknown('q')))));
+}
+
+#[test]
+fn escape() {
+    let source = "\"a\\\"b\\t\\u{E9}\" \"plain\" \"bad \\q\"";
+    let mut traverser = Traverser::from_str(source);
+    let escaped = string::Node::parse(&mut traverser).unwrap();
+    assert_eq!(escaped.data().raw, "a\\\"b\\t\\u{E9}");
+    assert_eq!(escaped.data().text(), Some("a\"b\t\u{E9}"));
+    
+    let _ = Node::parse(&mut traverser).unwrap();
+    assert!(matches!(string::Node::parse(&mut traverser).unwrap().data().segments[..], [Segment::Text(Cow::Borrowed("plain"))]));
+    
+    let _ = Node::parse(&mut traverser).unwrap();
+    let error = string::Node::parse(&mut traverser).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Other(string::Error::InvalidEscape { sequence: "\\q".into(), error: escape::Error::Unknown('q') }));
+    assert_eq!(&source[error.byte_offset..], "\\q\"");
+}
+
+#[test]
+fn interpolation() {
+    let mut traverser = Traverser::from_str("\"velocity [ velocity ] of [\"a\"]\\[x]\"");
+    let string = string::Node::parse(&mut traverser).unwrap();
+    let [Segment::Text(before), Segment::Interpolation(NodeVariant::Identifier(name)), Segment::Text(between), Segment::Interpolation(NodeVariant::String(nested)), Segment::Text(after)] = &string.data().segments[..] else {
+        panic!("unexpected segments {:?}", string.data().segments)
+    };
+    
+    assert_eq!(before, "velocity ");
+    assert_eq!(name.data().0, "velocity");
+    assert_eq!(between, " of ");
+    assert_eq!(nested.data().text(), Some("a"));
+    assert_eq!(after, "[x]");
+
+    let mut traverser = Traverser::from_str("\"[ ]\"");
+    assert_eq!(string::Node::parse(&mut traverser).unwrap_err().kind, ErrorKind::Other(string::Error::ExpectedExpression));
+}
+
+#[test]
+fn unterminated_string() {
+    for source in ["\"abc", "\"\"\"\n  abc\n", "##\"abc\"#", "\"a [b]"] {
+        let error = string::Node::parse(&mut Traverser::from_str(source)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Other(string::Error::Unterminated), "{}", source);
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::{Add, Neg};
 use thiserror::Error;
 use crate::core::{node, token};
 use crate::core::node::{identifier, number, ErrorKind, NodeVariant, Parsable, Traverser};
-use crate::core::token::Kind;
+use crate::core::token::{Keyword, Kind};
 use crate::Pbt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -11,7 +11,8 @@ pub enum Scale {
     X8,
     X16,
     X32,
-    X64
+    X64,
+    X128
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -21,12 +22,69 @@ pub enum NumericKind {
     Float
 }
 
+impl Scale {
+    pub const fn bits(self) -> u32 {
+        match self {
+            Scale::X8 => 8,
+            Scale::X16 => 16,
+            Scale::X32 => 32,
+            Scale::X64 => 64,
+            Scale::X128 => 128
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Numeric {
     pub scale: Scale,
     pub kind: NumericKind
 }
 
+impl Numeric {
+    /// Find the numeric type selected by a number literal suffix such as `u1` or `f4`. The number is
+    /// the width in bytes, matching the `integer1`..`float8` keywords.
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        let mut characters = suffix.chars();
+        let kind = match characters.next()? {
+            'i' => NumericKind::Integer,
+            'u' => NumericKind::Unsigned,
+            'f' => NumericKind::Float,
+            _ => return None
+        };
+        let scale = match characters.as_str() {
+            "1" => Scale::X8,
+            "2" => Scale::X16,
+            "4" => Scale::X32,
+            "8" => Scale::X64,
+            "16" if kind != NumericKind::Float => Scale::X128,
+            _ => return None
+        };
+
+        Some(Self { scale, kind })
+    }
+
+    /// The literal suffix that selects this type, the reverse of [`Numeric::from_suffix`].
+    pub fn suffix(self) -> String {
+        let kind = match self.kind {
+            NumericKind::Integer => 'i',
+            NumericKind::Unsigned => 'u',
+            NumericKind::Float => 'f'
+        };
+        format!("{}{}", kind, self.scale.bits() / 8)
+    }
+
+    /// The largest finite magnitude a float of this scale can hold. 8-bit floats use the E5M2 layout,
+    /// 16-bit floats are IEEE 754 half precision.
+    pub const fn float_max(self) -> f64 {
+        match self.scale {
+            Scale::X8 => 57344.0,
+            Scale::X16 => 65504.0,
+            Scale::X32 => f32::MAX as f64,
+            Scale::X64 | Scale::X128 => f64::MAX
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MainType<'a> {
     Numeric(Numeric),
@@ -50,55 +108,61 @@ pub struct Type<'a> {
 pub type Node<'a> = node::Node<MainType<'a>>;
 
 impl<'a> Node<'a> {
-    pub const KEYWORDS: [Kind<'a>; 14] = [
-        Kind::Identifier("integer1"),
-        Kind::Identifier("integer2"),
-        Kind::Identifier("integer4"),
-        Kind::Identifier("integer8"),
-        Kind::Identifier("unsigned1"),
-        Kind::Identifier("unsigned2"),
-        Kind::Identifier("unsigned4"),
-        Kind::Identifier("unsigned8"),
-        Kind::Identifier("float1"),
-        Kind::Identifier("float2"),
-        Kind::Identifier("float4"),
-        Kind::Identifier("float8"),
-        Kind::Identifier("logical"),
-        Kind::Identifier("string")
+    pub const KEYWORDS: [Kind<'a>; 16] = [
+        Kind::Keyword(Keyword::Integer1),
+        Kind::Keyword(Keyword::Integer2),
+        Kind::Keyword(Keyword::Integer4),
+        Kind::Keyword(Keyword::Integer8),
+        Kind::Keyword(Keyword::Integer16),
+        Kind::Keyword(Keyword::Unsigned1),
+        Kind::Keyword(Keyword::Unsigned2),
+        Kind::Keyword(Keyword::Unsigned4),
+        Kind::Keyword(Keyword::Unsigned8),
+        Kind::Keyword(Keyword::Unsigned16),
+        Kind::Keyword(Keyword::Float1),
+        Kind::Keyword(Keyword::Float2),
+        Kind::Keyword(Keyword::Float4),
+        Kind::Keyword(Keyword::Float8),
+        Kind::Keyword(Keyword::Logical),
+        Kind::Keyword(Keyword::String)
     ];
     
     pub const MODIFIERS: [Kind<'a>; 2] = [
-        Kind::Identifier("ref"),
-        Kind::Identifier("mut_ref")
+        Kind::Keyword(Keyword::Reference),
+        Kind::Keyword(Keyword::MutableReference)
     ];
 
     fn next_main_type(tokens: &mut Traverser<'a>) -> Result<MainType<'a>, node::Error<<Self as Parsable<'a>>::Error>> {
-        let keyword_identifier = identifier::Node::parse(tokens).map_err(|error| tokens.new_other_error(error))?;
+        let Ok(keyword) = tokens.expect_tokens::<()>(&Self::KEYWORDS) else {
+            return Ok(MainType::Other(identifier::Node::parse(tokens).map_err(|error| tokens.new_other_error(error))?));
+        };
         
         // todo: support generics
-        Ok(match keyword_identifier.data.0 {
-            "integer1"  => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X8  }),
-            "integer2"  => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X16 }),
-            "integer4"  => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X32 }),
-            "integer8"  => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X64 }),
-            "unsigned1" => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X8  }),
-            "unsigned2" => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X16 }),
-            "unsigned4" => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X32 }),
-            "unsigned8" => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X64 }),
-            "float1"    => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X8  }),
-            "float2"    => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X16 }),
-            "float4"    => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X32 }),
-            "float8"    => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X64 }),
-            "logical"   => MainType::Bool,
-            "string"    => MainType::String,
-            _ => MainType::Other(identifier::Node::parse(tokens).map_err(|error| tokens.new_other_error(error))?)
+        Ok(match keyword.kind() {
+            Kind::Keyword(Keyword::Integer1)   => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X8   }),
+            Kind::Keyword(Keyword::Integer2)   => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X16  }),
+            Kind::Keyword(Keyword::Integer4)   => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X32  }),
+            Kind::Keyword(Keyword::Integer8)   => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X64  }),
+            Kind::Keyword(Keyword::Integer16)  => MainType::Numeric(Numeric { kind: NumericKind::Integer,  scale: Scale::X128 }),
+            Kind::Keyword(Keyword::Unsigned1)  => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X8   }),
+            Kind::Keyword(Keyword::Unsigned2)  => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X16  }),
+            Kind::Keyword(Keyword::Unsigned4)  => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X32  }),
+            Kind::Keyword(Keyword::Unsigned8)  => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X64  }),
+            Kind::Keyword(Keyword::Unsigned16) => MainType::Numeric(Numeric { kind: NumericKind::Unsigned, scale: Scale::X128 }),
+            Kind::Keyword(Keyword::Float1)     => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X8   }),
+            Kind::Keyword(Keyword::Float2)     => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X16  }),
+            Kind::Keyword(Keyword::Float4)     => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X32  }),
+            Kind::Keyword(Keyword::Float8)     => MainType::Numeric(Numeric { kind: NumericKind::Float,    scale: Scale::X64  }),
+            Kind::Keyword(Keyword::Logical)    => MainType::Bool,
+            Kind::Keyword(Keyword::String)     => MainType::String,
+            _ => unreachable!()
         })
     }
     
     fn next_modifier(tokens: &mut Traverser<'a>) -> Result<Modifier, node::Error<<Self as Parsable<'a>>::Error>> {
         Ok(match tokens.expect_tokens(&Self::MODIFIERS)?.kind() {
-            Kind::Identifier("ref") => Modifier::Reference,
-            Kind::Identifier("mut_ref") => Modifier::MutableReference,
+            Kind::Keyword(Keyword::Reference) => Modifier::Reference,
+            Kind::Keyword(Keyword::MutableReference) => Modifier::MutableReference,
             _ => unreachable!()
         })
     }
@@ -108,7 +172,7 @@ impl<'a> Parsable<'a> for Node<'a> {
     type Error = node::Error<identifier::Error>;
 
     fn parse(tokens: &mut Traverser<'a>) -> Result<Self, node::Error<Self::Error>> {
-        let start = tokens.token_offset();
+        let start = tokens.offset();
         let main = Self::next_main_type(tokens)?;
         tokens.end(start, main)
     }
@@ -1,34 +1,40 @@
 use thiserror::Error;
 use crate::core::node;
 use crate::core::node::{NodeVariant, Parsable, Traverser};
-use crate::core::token::Kind;
+use crate::core::token::{Kind, LineEnding};
 use crate::Pbt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WhiteSpace {
     Space,
     Tab,
-    NewLine
+    NewLine(LineEnding)
 }
 
 pub type Node = node::Node<Box<[WhiteSpace]>>;
 
 impl<'a> Node {
-    pub const WHITESPACE_TOKENS: [Kind<'a>; 3] = [Kind::Space, Kind::Tab, Kind::NewLine];
+    pub const WHITESPACE_TOKENS: [Kind<'a>; 5] = [
+        Kind::Space,
+        Kind::Tab,
+        Kind::NewLine(LineEnding::LineFeed),
+        Kind::NewLine(LineEnding::CarriageReturnLineFeed),
+        Kind::NewLine(LineEnding::CarriageReturn)
+    ];
 }
 
 impl<'a> Parsable<'a> for Node {
     type Error = ();
 
     fn parse(traverser: &mut Traverser<'a>) -> Result<Self, node::Error<Self::Error>> {
-        let start = traverser.token_offset();
+        let start = traverser.offset();
         let mut accumulator = Vec::new();
         loop {
             let Ok(token) = traverser.expect_tokens::<()>(&Self::WHITESPACE_TOKENS) else { break };
             let whitespace = match token.kind() {
                 Kind::Space => WhiteSpace::Space,
                 Kind::Tab => WhiteSpace::Tab,
-                Kind::NewLine => WhiteSpace::NewLine,
+                Kind::NewLine(ending) => WhiteSpace::NewLine(*ending),
                 _ => unreachable!()
             };
             accumulator.push(whitespace);
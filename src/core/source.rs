@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod test;
+
+use std::borrow::Cow;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf16LittleEndian,
+    Utf16BigEndian
+}
+
+impl Encoding {
+    pub const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+    pub const UTF16_LITTLE_ENDIAN_BOM: [u8; 2] = [0xFF, 0xFE];
+    pub const UTF16_BIG_ENDIAN_BOM: [u8; 2] = [0xFE, 0xFF];
+
+    /// Detect the encoding from a leading byte-order mark, returning the length of the mark. Sources
+    /// without a mark are assumed to be UTF-8.
+    pub fn detect(bytes: &[u8]) -> (Self, usize) {
+        if bytes.starts_with(&Self::UTF8_BOM) { return (Self::Utf8, Self::UTF8_BOM.len()) }
+        if bytes.starts_with(&Self::UTF16_LITTLE_ENDIAN_BOM) { return (Self::Utf16LittleEndian, Self::UTF16_LITTLE_ENDIAN_BOM.len()) }
+        if bytes.starts_with(&Self::UTF16_BIG_ENDIAN_BOM) { return (Self::Utf16BigEndian, Self::UTF16_BIG_ENDIAN_BOM.len()) }
+        (Self::Utf8, 0)
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Invalid UTF-8 sequence at byte {byte_offset}")]
+    InvalidUtf8 { byte_offset: usize },
+    #[error("Unpaired UTF-16 surrogate at byte {byte_offset}")]
+    InvalidUtf16 { byte_offset: usize },
+    #[error("UTF-16 source has an odd number of bytes, the last byte at {byte_offset} is incomplete")]
+    IncompleteUtf16 { byte_offset: usize }
+}
+
+/// Decoded source text, ready to be handed to [`crate::core::token::Iterator::from_str`].
+///
+/// Byte offsets produced by the lexer are relative to [`Source::text`], which no longer contains the
+/// byte-order mark. Error offsets are relative to the original bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source<'a> {
+    text: Cow<'a, str>,
+    encoding: Encoding,
+    byte_order_mark: bool
+}
+
+impl<'a> Source<'a> {
+    pub fn text(&self) -> &str { &self.text }
+    pub fn into_text(self) -> Cow<'a, str> { self.text }
+    pub const fn encoding(&self) -> Encoding { self.encoding }
+    pub const fn byte_order_mark(&self) -> bool { self.byte_order_mark }
+
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (encoding, mark_length) = Encoding::detect(bytes);
+        let body = &bytes[mark_length..];
+
+        let text = match encoding {
+            Encoding::Utf8 => Cow::Borrowed(std::str::from_utf8(body).map_err(|error| Error::InvalidUtf8 {
+                byte_offset: mark_length + error.valid_up_to()
+            })?),
+            Encoding::Utf16LittleEndian => Cow::Owned(Self::decode_utf16(body, mark_length, u16::from_le_bytes)?),
+            Encoding::Utf16BigEndian => Cow::Owned(Self::decode_utf16(body, mark_length, u16::from_be_bytes)?)
+        };
+
+        Ok(Self { text, encoding, byte_order_mark: mark_length != 0 })
+    }
+
+    fn decode_utf16(body: &[u8], mark_length: usize, unit: fn([u8; 2]) -> u16) -> Result<String, Error> {
+        if !body.len().is_multiple_of(2) { return Err(Error::IncompleteUtf16 { byte_offset: mark_length + body.len() - 1 }) }
+
+        let units = body.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+        let mut text = String::with_capacity(body.len() / 2);
+        let mut byte_offset = mark_length;
+
+        for character in char::decode_utf16(units) {
+            let character = character.map_err(|_| Error::InvalidUtf16 { byte_offset })?;
+            byte_offset += character.len_utf16() * 2;
+            text.push(character);
+        }
+
+        Ok(text)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::core::source::{Encoding, Error, Source};
+
+#[test]
+fn utf8_byte_order_mark() {
+    let source = Source::from_bytes(b"\xEF\xBB\xBFvar x").unwrap();
+    assert_eq!(source.text(), "var x");
+    assert!(source.byte_order_mark());
+}
+
+#[test]
+fn utf16() {
+    let little = Source::from_bytes(b"\xFF\xFEv\x00a\x00r\x00").unwrap();
+    let big = Source::from_bytes(b"\xFE\xFF\x00v\x00a\x00r").unwrap();
+    assert_eq!((little.text(), little.encoding()), ("var", Encoding::Utf16LittleEndian));
+    assert_eq!((big.text(), big.encoding()), ("var", Encoding::Utf16BigEndian));
+    assert_eq!(Source::from_bytes(b"\xFF\xFEv\x00\x00\xD8"), Err(Error::InvalidUtf16 { byte_offset: 4 }));
+}
+
+#[test]
+fn invalid_utf8() {
+    assert_eq!(Source::from_bytes(b"var \xC3x"), Err(Error::InvalidUtf8 { byte_offset: 4 }));
+}
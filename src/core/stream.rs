@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod test;
+
+use std::io;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+use thiserror::Error;
+use crate::core::symbol::{Interner, Symbol};
+use crate::core::token;
+use crate::core::token::{Diagnostic, Dialect, DocTarget, Radix, State};
+
+/// A token kind that owns its text, or refers to it through an interned symbol, so that it can
+/// outlive the chunk it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+    /// A kind that carries no text of its own.
+    Plain(token::Kind<'static>),
+    Identifier(Symbol),
+    Number { text: Box<str>, radix: Radix },
+    StringText(Box<str>),
+    EscapeSequence(Box<str>),
+    RawString { delimiters: usize, text: Box<str> },
+    Comment(Box<str>),
+    DocComment { target: DocTarget, text: Box<str> }
+}
+
+impl Kind {
+    fn from_kind(kind: &token::Kind, interner: &Interner) -> Self {
+        match *kind {
+            token::Kind::Identifier(identifier) => Kind::Identifier(interner.intern(identifier)),
+            token::Kind::Number(number) => Kind::Number { text: number.text.into(), radix: number.radix },
+            token::Kind::StringText(text) => Kind::StringText(text.into()),
+            token::Kind::EscapeSequence(escape) => Kind::EscapeSequence(escape.into()),
+            token::Kind::RawString(string) => Kind::RawString { delimiters: string.delimiters, text: string.text.into() },
+            token::Kind::Comment(comment) => Kind::Comment(comment.into()),
+            token::Kind::DocComment(comment) => Kind::DocComment { target: comment.target, text: comment.text.into() },
+            other => Kind::Plain(other.into_static().expect("kinds that borrow no text have no lifetime"))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    kind: Kind,
+    byte_start: usize,
+    byte_length: usize
+}
+
+impl Token {
+    pub const fn kind(&self) -> &Kind { &self.kind }
+    pub const fn byte_length(&self) -> usize { self.byte_length }
+    pub const fn byte_start(&self) -> usize { self.byte_start }
+    pub const fn byte_end(&self) -> usize { self.byte_start + self.byte_length }
+    pub const fn byte_range(&self) -> Range<usize> { self.byte_start()..self.byte_end() }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Failed to read the source")]
+    Io(#[from] io::Error),
+    #[error("Invalid UTF-8 sequence at byte {byte_offset}")]
+    InvalidUtf8 { byte_offset: usize }
+}
+
+/// Lexes a UTF-8 source as it is read, keeping only the text of the token being lexed in memory.
+/// Identifiers are interned and other text is owned by the tokens.
+///
+/// A token is only produced once the character after it has been read, so tokens and UTF-8 sequences
+/// split between reads are lexed whole. Invalid UTF-8 ends the stream with an error once the text
+/// before it has been lexed.
+#[derive(Debug)]
+pub struct Stream<R: BufRead> {
+    reader: R,
+    /// Decoded text that has not been fully lexed, starting at `byte_base` in the source.
+    buffer: String,
+    byte_base: usize,
+    /// The length of the lexed prefix of `buffer`.
+    consumed: usize,
+    /// The bytes of a UTF-8 sequence that was split by the end of the last read.
+    pending: Vec<u8>,
+    reached_end: bool,
+    error: Option<Error>,
+    state: State,
+    interner: Interner,
+    dialect: Dialect,
+    diagnostics: Vec<Diagnostic>
+}
+
+impl<R: Read> Stream<BufReader<R>> {
+    pub fn from_reader(reader: R) -> Self { Self::from_buf_reader(BufReader::new(reader)) }
+}
+
+impl<R: BufRead> Stream<R> {
+    pub fn from_buf_reader(reader: R) -> Self { Self::with_interner(reader, Interner::default()) }
+
+    pub fn with_interner(reader: R, interner: Interner) -> Self { Self::with_dialect(reader, interner, Dialect::DEFAULT) }
+
+    pub fn with_dialect(reader: R, interner: Interner, dialect: Dialect) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            byte_base: 0,
+            consumed: 0,
+            pending: Vec::new(),
+            reached_end: false,
+            error: None,
+            state: State::default(),
+            interner,
+            dialect,
+            diagnostics: Vec::new()
+        }
+    }
+
+    pub const fn interner(&self) -> &Interner { &self.interner }
+    pub const fn dialect(&self) -> Dialect { self.dialect }
+
+    /// Problems found in the tokens produced so far.
+    pub fn diagnostics(&self) -> &[Diagnostic] { &self.diagnostics }
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> { std::mem::take(&mut self.diagnostics) }
+
+    /// Lex the next token in the buffer, if the buffer holds all of it and the text the lexer looks
+    /// ahead at to decide it.
+    fn next_buffered(&mut self) -> Option<Token> {
+        let mut tokens = token::Iterator::resume(&self.buffer, self.consumed, self.state.clone(), self.dialect);
+        let token = tokens.next()?;
+        let is_complete = token.byte_end() + token::Iterator::lookahead(&self.buffer, &token) <= self.buffer.len();
+        if !is_complete && !self.reached_end { return None }
+
+        self.state = tokens.state().clone();
+        self.consumed = token.byte_end();
+        self.diagnostics.extend(tokens.take_diagnostics().into_iter().map(|diagnostic| Diagnostic {
+            error: diagnostic.error,
+            byte_range: self.byte_base + diagnostic.byte_range.start..self.byte_base + diagnostic.byte_range.end
+        }));
+
+        Some(Token {
+            kind: Kind::from_kind(token.kind(), &self.interner),
+            byte_start: self.byte_base + token.byte_start(),
+            byte_length: token.byte_length()
+        })
+    }
+
+    /// Drop the lexed text from the buffer and read until the text left in it has at least doubled. A
+    /// token that crosses the end of the buffer is lexed again from its start, so growing the buffer
+    /// geometrically keeps long tokens linear.
+    fn fill(&mut self) -> Result<(), io::Error> {
+        self.buffer.drain(..self.consumed);
+        self.byte_base += self.consumed;
+        self.consumed = 0;
+
+        let target = (self.buffer.len() * 2).max(1);
+        while !self.reached_end && self.buffer.len() < target { self.read()? }
+        Ok(())
+    }
+
+    /// Decode the next read onto the end of the buffer. Only reading can fail here, a decoding error
+    /// is kept until the text before it has been lexed.
+    fn read(&mut self) -> Result<(), io::Error> {
+        let chunk = self.reader.fill_buf()?;
+        let byte_offset = self.byte_base + self.buffer.len();
+        if chunk.is_empty() {
+            self.reached_end = true;
+            if !self.pending.is_empty() { self.error = Some(Error::InvalidUtf8 { byte_offset }) }
+            return Ok(());
+        }
+
+        self.pending.extend_from_slice(chunk);
+        let length = chunk.len();
+        self.reader.consume(length);
+
+        let (valid_up_to, invalid) = match std::str::from_utf8(&self.pending) {
+            Ok(_) => (self.pending.len(), false),
+            Err(error) => (error.valid_up_to(), error.error_len().is_some())
+        };
+        self.buffer.push_str(std::str::from_utf8(&self.pending[..valid_up_to]).expect("the prefix was validated"));
+        self.pending.drain(..valid_up_to);
+
+        if invalid {
+            self.reached_end = true;
+            self.pending.clear();
+            self.error = Some(Error::InvalidUtf8 { byte_offset: byte_offset + valid_up_to });
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for Stream<R> {
+    type Item = Result<Token, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.next_buffered() { return Some(Ok(token)) }
+            if self.reached_end { return self.error.take().map(Err) }
+            if let Err(error) = self.fill() { return Some(Err(Error::Io(error))) }
+        }
+    }
+}
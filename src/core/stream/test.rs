@@ -0,0 +1,41 @@
+use std::io::BufReader;
+use crate::core::stream::{Error, Kind, Stream};
+use crate::core::token;
+
+#[test]
+fn chunks() {
+    let source = "caf\u{E9} \u{1F600}x\r\n12e-3 /* a /* b */ */ \"//\" \"\"\"\n  a\n  \"\"\" ##\"a\"## x";
+    let mut lexer = token::Iterator::from_str(source);
+    let expected = lexer.by_ref().collect::<Vec<_>>();
+
+    // reading a byte at a time splits every multibyte character and token.
+    let mut stream = Stream::from_buf_reader(BufReader::with_capacity(1, source.as_bytes()));
+    let tokens = stream.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(tokens.len(), expected.len());
+    for (token, expected) in tokens.iter().zip(&expected) {
+        assert_eq!(token.byte_range(), expected.byte_range());
+        assert_eq!(token.kind(), &Kind::from_kind(expected.kind(), stream.interner()));
+    }
+    assert_eq!(tokens[0].kind(), &Kind::Identifier(stream.interner().intern("caf\u{E9}")));
+    assert_eq!(tokens[2].kind(), &Kind::Plain(token::Kind::Other('\u{1F600}')));
+    assert_eq!(tokens[5].kind(), &Kind::Number { text: "12e-3".into(), radix: token::Radix::Decimal });
+    assert_eq!(stream.diagnostics(), lexer.diagnostics());
+}
+
+#[test]
+fn invalid_utf8() {
+    let mut stream = Stream::from_reader(&b"ab \xFF"[..]);
+    assert_eq!(stream.next().unwrap().unwrap().kind(), &Kind::Identifier(stream.interner().intern("ab")));
+    assert_eq!(stream.next().unwrap().unwrap().kind(), &Kind::Plain(token::Kind::Space));
+    assert!(matches!(stream.next(), Some(Err(Error::InvalidUtf8 { byte_offset: 3 }))));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn long_token() {
+    let source = format!("/* {} */ x", "a".repeat(100_000));
+    let mut stream = Stream::from_buf_reader(BufReader::with_capacity(1, source.as_bytes()));
+    assert_eq!(stream.next().unwrap().unwrap().kind(), &Kind::Comment(source[..source.len() - 2].into()));
+    assert_eq!(stream.by_ref().count(), 2);
+}
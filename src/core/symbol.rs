@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod test;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// A handle to an interned name. Symbols from the same [`Interner`] compare equal exactly when their
+/// names are equal after NFC normalisation, symbols from different interners never compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    interner: u32,
+    index: u32
+}
+
+impl Symbol {
+    pub const fn index(self) -> usize { self.index as usize }
+}
+
+#[derive(Debug)]
+struct Names {
+    /// Unique to each interner, so that its symbols cannot be mistaken for those of another.
+    id: u32,
+    symbols: HashMap<Rc<str>, Symbol>,
+    names: Vec<Rc<str>>
+}
+
+/// Maps names to [`Symbol`]s. Cloning an interner shares it, so the traversers of every file in a
+/// compilation can hand out comparable symbols.
+#[derive(Debug, Clone)]
+pub struct Interner(Rc<RefCell<Names>>);
+
+impl Default for Interner {
+    fn default() -> Self {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        Self(Rc::new(RefCell::new(Names { id, symbols: HashMap::new(), names: Vec::new() })))
+    }
+}
+
+impl Interner {
+    pub fn intern(&self, name: &str) -> Symbol {
+        let name = normalize(name);
+        let mut names = self.0.borrow_mut();
+        if let Some(&symbol) = names.symbols.get(name.as_ref()) { return symbol }
+
+        let index = u32::try_from(names.names.len()).expect("too many interned names");
+        let symbol = Symbol { interner: names.id, index };
+        let name: Rc<str> = Rc::from(name.as_ref());
+        names.names.push(name.clone());
+        names.symbols.insert(name, symbol);
+        symbol
+    }
+
+    /// Find the symbol of a name without interning it.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.0.borrow().symbols.get(normalize(name).as_ref()).copied()
+    }
+
+    /// The normalised name of a symbol. Panics if the symbol came from a different interner.
+    pub fn resolve(&self, symbol: Symbol) -> Rc<str> {
+        let names = self.0.borrow();
+        assert_eq!(symbol.interner, names.id, "the symbol came from a different interner");
+        names.names[symbol.index()].clone()
+    }
+
+    pub fn len(&self) -> usize { self.0.borrow().names.len() }
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+/// The NFC normalised form of a name, borrowed when it is already normalised.
+pub fn normalize(name: &str) -> Cow<'_, str> {
+    match is_nfc_quick(name.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(name),
+        _ => Cow::Owned(name.nfc().collect())
+    }
+}
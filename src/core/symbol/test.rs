@@ -0,0 +1,27 @@
+use crate::core::symbol::Interner;
+
+#[test]
+fn intern() {
+    let interner = Interner::default();
+    let composed = interner.intern("caf\u{E9}");
+    let other = interner.intern("tea");
+
+    assert_eq!(interner.intern("cafe\u{301}"), composed);
+    assert_ne!(composed, other);
+    assert_eq!(interner.get("tea"), Some(other));
+    assert_eq!(interner.get("coffee"), None);
+    assert_eq!(&*interner.resolve(composed), "caf\u{E9}");
+
+    // clones share their names.
+    assert_eq!(interner.clone().intern("tea"), other);
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn separate_interners() {
+    let first = Interner::default();
+    let second = Interner::default();
+    assert_eq!(first.intern("table").index(), second.intern("chair").index());
+    assert_ne!(first.intern("table"), second.intern("chair"));
+    assert_ne!(first.intern("table"), second.intern("table"));
+}
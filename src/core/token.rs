@@ -1,15 +1,297 @@
+#[cfg(test)]
+mod test;
+
 use std::iter;
 use std::iter::Peekable;
+use std::ops::Range;
 use std::str::CharIndices;
+use thiserror::Error;
+use unicode_xid::UnicodeXID;
+
+/// The character sequence a [`Kind::NewLine`] was written with, kept so that the source can be
+/// printed back losslessly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    LineFeed,
+    CarriageReturnLineFeed,
+    CarriageReturn
+}
+
+impl LineEnding {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::LineFeed => "\n",
+            LineEnding::CarriageReturnLineFeed => "\r\n",
+            LineEnding::CarriageReturn => "\r"
+        }
+    }
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hexadecimal
+}
+
+impl Radix {
+    /// The radix selected by the letter following a leading `0`, as in `0x`, `0b` and `0o`.
+    pub const fn from_prefix(character: char) -> Option<Self> {
+        match character {
+            'b' => Some(Radix::Binary),
+            'o' => Some(Radix::Octal),
+            'x' => Some(Radix::Hexadecimal),
+            _ => None
+        }
+    }
+
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Radix::Binary => "0b",
+            Radix::Octal => "0o",
+            Radix::Decimal => "",
+            Radix::Hexadecimal => "0x"
+        }
+    }
+
+    pub const fn base(self) -> u32 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16
+        }
+    }
+}
+
+/// A run of digits lexed as one token, including its radix prefix and any `_` separators.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NumberLiteral<'a> {
+    pub text: &'a str,
+    pub radix: Radix
+}
+
+impl<'a> NumberLiteral<'a> {
+    /// The literal text without its radix prefix.
+    pub fn digits(&self) -> &'a str { &self.text[self.radix.prefix().len()..] }
+}
+
+/// A reserved word. Keywords can still be used as names by escaping them with a leading `_`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keyword {
+    Fun,
+    Var,
+    Struct,
+    Depend,
+    Derive,
+    Integer1,
+    Integer2,
+    Integer4,
+    Integer8,
+    Integer16,
+    Unsigned1,
+    Unsigned2,
+    Unsigned4,
+    Unsigned8,
+    Unsigned16,
+    Float1,
+    Float2,
+    Float4,
+    Float8,
+    Logical,
+    String,
+    Reference,
+    MutableReference
+}
+
+impl Keyword {
+    pub const ALL: [Keyword; 23] = [
+        Keyword::Fun,
+        Keyword::Var,
+        Keyword::Struct,
+        Keyword::Depend,
+        Keyword::Derive,
+        Keyword::Integer1,
+        Keyword::Integer2,
+        Keyword::Integer4,
+        Keyword::Integer8,
+        Keyword::Integer16,
+        Keyword::Unsigned1,
+        Keyword::Unsigned2,
+        Keyword::Unsigned4,
+        Keyword::Unsigned8,
+        Keyword::Unsigned16,
+        Keyword::Float1,
+        Keyword::Float2,
+        Keyword::Float4,
+        Keyword::Float8,
+        Keyword::Logical,
+        Keyword::String,
+        Keyword::Reference,
+        Keyword::MutableReference
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Keyword::Fun => "fun",
+            Keyword::Var => "var",
+            Keyword::Struct => "struct",
+            Keyword::Depend => "depend",
+            Keyword::Derive => "derive",
+            Keyword::Integer1 => "integer1",
+            Keyword::Integer2 => "integer2",
+            Keyword::Integer4 => "integer4",
+            Keyword::Integer8 => "integer8",
+            Keyword::Integer16 => "integer16",
+            Keyword::Unsigned1 => "unsigned1",
+            Keyword::Unsigned2 => "unsigned2",
+            Keyword::Unsigned4 => "unsigned4",
+            Keyword::Unsigned8 => "unsigned8",
+            Keyword::Unsigned16 => "unsigned16",
+            Keyword::Float1 => "float1",
+            Keyword::Float2 => "float2",
+            Keyword::Float4 => "float4",
+            Keyword::Float8 => "float8",
+            Keyword::Logical => "logical",
+            Keyword::String => "string",
+            Keyword::Reference => "ref",
+            Keyword::MutableReference => "mut_ref"
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|keyword| keyword.as_str() == identifier)
+    }
+}
+
+impl std::fmt::Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocTarget {
+    /// A `///` comment documenting the item that follows it.
+    Item,
+    /// A `//!` comment documenting the module it is written in.
+    Module
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DocComment<'a> {
+    pub target: DocTarget,
+    /// The whole comment, including its marker.
+    pub text: &'a str,
+    /// The documentation after the marker, without the single space that usually follows it.
+    pub content: &'a str
+}
+
+impl<'a> DocComment<'a> {
+    /// Find the documentation a line comment carries, if it is a doc comment. Like `///`, a comment
+    /// starting with four or more slashes is an ordinary comment.
+    pub fn from_line_comment(text: &'a str) -> Option<Self> {
+        let (target, content) = if let Some(content) = text.strip_prefix("//!") {
+            (DocTarget::Module, content)
+        } else {
+            let content = text.strip_prefix("///")?;
+            if content.starts_with('/') { return None }
+            (DocTarget::Item, content)
+        };
+
+        Some(Self { target, text, content: content.strip_prefix(' ').unwrap_or(content) })
+    }
+}
+
+/// A `#"..."#` string, in which backslashes, quotes and brackets are literal. The string ends at a
+/// quote followed by as many `#` as it started with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawString<'a> {
+    pub delimiters: usize,
+    /// The whole string, including its delimiters.
+    pub text: &'a str,
+    pub content: &'a str
+}
+
+impl<'a> RawString<'a> {
+    /// Find the raw string at the start of some text, which runs to the end of the text if it is never
+    /// closed.
+    pub fn from_text(text: &'a str) -> Option<Self> {
+        let delimiters = text.len() - text.trim_start_matches('#').len();
+        if delimiters == 0 { return None }
+        let rest = text[delimiters..].strip_prefix('"')?;
+
+        let closing = format!("\"{}", "#".repeat(delimiters));
+        let (content, length) = match rest.find(&closing) {
+            Some(end) => (&rest[..end], delimiters + 1 + end + closing.len()),
+            None => (rest, text.len())
+        };
+
+        Some(Self { delimiters, text: &text[..length], content })
+    }
+
+    pub const fn is_terminated(&self) -> bool {
+        self.text.len() == self.delimiters * 2 + "\"\"".len() + self.content.len()
+    }
+}
+
+/// How a string or character literal that the lexer reads in string mode was opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringForm {
+    /// A `"` string, which ends at the next unescaped `"`.
+    #[default]
+    Quoted,
+    /// A `"""` string, which may span lines and ends at the next `"""`.
+    MultiLine,
+    /// A `'` character literal, which ends at the next unescaped `'` or line break and cannot be
+    /// interpolated.
+    Character
+}
+
+impl StringForm {
+    pub const fn quote(self) -> &'static str {
+        match self {
+            StringForm::Quoted => "\"",
+            StringForm::MultiLine => "\"\"\"",
+            StringForm::Character => "'"
+        }
+    }
+
+    pub const fn quote_kind(self) -> Kind<'static> {
+        match self {
+            StringForm::Quoted => Kind::StringQuote,
+            StringForm::MultiLine => Kind::MultiLineQuote,
+            StringForm::Character => Kind::CharacterQuote
+        }
+    }
+
+    /// Whether a character ends a run of literal text in this form.
+    const fn ends_text(self, character: char) -> bool {
+        match self {
+            StringForm::Quoted | StringForm::MultiLine => matches!(character, '"' | '[' | '\\'),
+            StringForm::Character => matches!(character, '\'' | '\\' | '\n' | '\r')
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Kind<'a> {
     #[default]
     Space,
     Tab,
-    NewLine,
+    NewLine(LineEnding),
     Identifier(&'a str),
-    Digit(u8),
+    Keyword(Keyword),
+    Number(NumberLiteral<'a>),
 
     OpeningBracket,
     ClosingBracket,
@@ -25,9 +307,23 @@ pub enum Kind<'a> {
     Separator,
     Equal,
     StringQuote,
+    /// The `"""` opening or closing a multi-line string.
+    MultiLineQuote,
+    RawString(RawString<'a>),
     CharacterQuote,
     Escape,
-    Comment,
+    /// A run of literal text inside a string.
+    StringText(&'a str),
+    /// A backslash and the character it escapes inside a string. `\u{..}` and `\x..` escapes include
+    /// the code that follows them.
+    EscapeSequence(&'a str),
+    /// The `[` opening an interpolated expression inside a string.
+    InterpolationStart,
+    /// The `]` closing an interpolated expression, returning to the string.
+    InterpolationEnd,
+    /// A `//` line comment or a nestable `/* */` block comment, including its delimiters.
+    Comment(&'a str),
+    DocComment(DocComment<'a>),
 
     Other(char)
 }
@@ -35,12 +331,36 @@ pub enum Kind<'a> {
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Token<'a> {
     kind: Kind<'a>,
+    byte_start: usize,
     byte_length: usize
 }
 
 impl<'a> Token<'a> {
-    pub fn kind(&self) -> &Kind<'a> { &self.kind }
-    pub fn byte_length(&self) -> usize { self.byte_length }
+    pub const fn kind(&self) -> &Kind<'a> { &self.kind }
+    pub const fn byte_length(&self) -> usize { self.byte_length }
+    pub const fn byte_start(&self) -> usize { self.byte_start }
+    pub const fn byte_end(&self) -> usize { self.byte_start + self.byte_length }
+    
+    /// The range of bytes this token covers in the source it was lexed from.
+    pub const fn byte_range(&self) -> Range<usize> { self.byte_start()..self.byte_end() }
+
+    /// Move this token to another source that contains the same text at `byte_start`, such as an
+    /// edited copy of the source it was lexed from.
+    pub fn rebase<'b>(&self, source: &'b str, byte_start: usize) -> Token<'b> {
+        let text = &source[byte_start..byte_start + self.byte_length];
+        let kind = match self.kind {
+            Kind::Identifier(_) => Kind::Identifier(text),
+            Kind::Number(number) => Kind::Number(NumberLiteral { text, radix: number.radix }),
+            Kind::Comment(_) => Kind::Comment(text),
+            Kind::StringText(_) => Kind::StringText(text),
+            Kind::RawString(string) => Kind::RawString(RawString { delimiters: string.delimiters, text, content: &text[string.delimiters + 1..][..string.content.len()] }),
+            Kind::EscapeSequence(_) => Kind::EscapeSequence(text),
+            Kind::DocComment(comment) => Kind::DocComment(DocComment { target: comment.target, text, content: &text[comment.text.len() - comment.content.len()..] }),
+            other => other.into_static().expect("kinds that borrow no text have no lifetime")
+        };
+
+        Token { kind, byte_start, byte_length: self.byte_length }
+    }
 }
 
 impl<'a> std::fmt::Display for Kind<'a> {
@@ -48,9 +368,10 @@ impl<'a> std::fmt::Display for Kind<'a> {
         match self {
             Kind::Space => write!(f, " "),
             Kind::Tab => write!(f, "\t"),
-            Kind::NewLine => write!(f, "\n"),
+            Kind::NewLine(ending) => write!(f, "{}", ending),
             Kind::Identifier(identifier) => write!(f, "{}", identifier),
-            Kind::Digit(digit) => write!(f, "{}", digit),
+            Kind::Keyword(keyword) => write!(f, "{}", keyword),
+            Kind::Number(number) => write!(f, "{}", number.text),
             Kind::OpeningBracket => write!(f, "["),
             Kind::ClosingBracket => write!(f, "]"),
             Kind::OpeningChevron => write!(f, "<"),
@@ -64,14 +385,53 @@ impl<'a> std::fmt::Display for Kind<'a> {
             Kind::Separator => write!(f, "|"),
             Kind::Equal => write!(f, "="),
             Kind::StringQuote => write!(f, "\""),
+            Kind::MultiLineQuote => write!(f, "\"\"\""),
+            Kind::RawString(string) => write!(f, "{}", string.text),
             Kind::CharacterQuote => write!(f, "'"),
             Kind::Escape => write!(f, "\\"),
-            Kind::Comment => write!(f, "/"),
+            Kind::StringText(text) => write!(f, "{}", text),
+            Kind::EscapeSequence(escape) => write!(f, "{}", escape),
+            Kind::InterpolationStart => write!(f, "["),
+            Kind::InterpolationEnd => write!(f, "]"),
+            Kind::Comment(comment) => write!(f, "{}", comment),
+            Kind::DocComment(comment) => write!(f, "{}", comment.text),
             Kind::Other(other) => write!(f, "{}", other),
         }
     }
 }
 
+impl Kind<'_> {
+    /// The kind with its lifetime dropped, if it does not borrow any text from the source.
+    pub const fn into_static(self) -> Option<Kind<'static>> {
+        Some(match self {
+            Kind::Space => Kind::Space,
+            Kind::Tab => Kind::Tab,
+            Kind::NewLine(ending) => Kind::NewLine(ending),
+            Kind::Keyword(keyword) => Kind::Keyword(keyword),
+            Kind::OpeningBracket => Kind::OpeningBracket,
+            Kind::ClosingBracket => Kind::ClosingBracket,
+            Kind::OpeningChevron => Kind::OpeningChevron,
+            Kind::ClosingChevron => Kind::ClosingChevron,
+            Kind::Negate => Kind::Negate,
+            Kind::IdentifierEscape => Kind::IdentifierEscape,
+            Kind::Path => Kind::Path,
+            Kind::Macro => Kind::Macro,
+            Kind::Decimal => Kind::Decimal,
+            Kind::Stop => Kind::Stop,
+            Kind::Separator => Kind::Separator,
+            Kind::Equal => Kind::Equal,
+            Kind::StringQuote => Kind::StringQuote,
+            Kind::MultiLineQuote => Kind::MultiLineQuote,
+            Kind::CharacterQuote => Kind::CharacterQuote,
+            Kind::Escape => Kind::Escape,
+            Kind::InterpolationStart => Kind::InterpolationStart,
+            Kind::InterpolationEnd => Kind::InterpolationEnd,
+            Kind::Other(character) => Kind::Other(character),
+            Kind::Identifier(_) | Kind::Number(_) | Kind::StringText(_) | Kind::EscapeSequence(_) | Kind::RawString(_) | Kind::Comment(_) | Kind::DocComment(_) => return None
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Mapping<'a> {
     pub character: char,
@@ -79,10 +439,10 @@ pub struct Mapping<'a> {
 }
 
 impl<'a> Kind<'a> {
-    pub const MAPPINGS: [Mapping<'a>; 19] = [
+    pub const MAPPINGS: [Mapping<'a>; 18] = [
         Mapping { character: ' ',  token: Kind::Space            },
         Mapping { character: '\t', token: Kind::Tab              },
-        Mapping { character: '\n', token: Kind::NewLine          },
+        Mapping { character: '\n', token: Kind::NewLine(LineEnding::LineFeed) },
         Mapping { character: '[',  token: Kind::OpeningBracket   },
         Mapping { character: ']',  token: Kind::ClosingBracket   },
         Mapping { character: '<',  token: Kind::OpeningChevron   },
@@ -97,49 +457,364 @@ impl<'a> Kind<'a> {
         Mapping { character: '=',  token: Kind::Equal            },
         Mapping { character: '"',  token: Kind::StringQuote      },
         Mapping { character: '\'', token: Kind::CharacterQuote   },
-        Mapping { character: '\\',  token: Kind::Escape          },
-        Mapping { character: '/',  token: Kind::Comment          }
+        Mapping { character: '\\',  token: Kind::Escape          }
     ];
 }
 
+/// The characters a lexer gives meaning to outside of strings and comments. The default dialect writes
+/// decimals as `1,5` and ends statements with `.`, the dot-decimal dialect writes `1.5` and `;`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dialect {
+    pub mappings: &'static [Mapping<'static>]
+}
+
+impl Dialect {
+    pub const DEFAULT: Self = Self { mappings: &Kind::MAPPINGS };
+    pub const DOT_DECIMAL: Self = Self { mappings: &Self::DOT_DECIMAL_MAPPINGS };
+
+    const DOT_DECIMAL_MAPPINGS: [Mapping<'static>; 18] = Self::with_punctuation(&Kind::MAPPINGS, '.', ';');
+
+    /// Copy a mapping table, writing `decimal` for [`Kind::Decimal`] and `stop` for [`Kind::Stop`].
+    const fn with_punctuation<const N: usize>(mappings: &[Mapping<'static>; N], decimal: char, stop: char) -> [Mapping<'static>; N] {
+        let mut mappings = *mappings;
+        let mut index = 0;
+
+        while index < N {
+            match mappings[index].token {
+                Kind::Decimal => mappings[index].character = decimal,
+                Kind::Stop => mappings[index].character = stop,
+                _ => ()
+            }
+            index += 1;
+        }
+
+        mappings
+    }
+
+    /// The token a character is lexed as on its own.
+    pub fn kind(self, character: char) -> Option<Kind<'static>> {
+        self.mappings.iter().find(|mapping| mapping.character == character).map(|mapping| mapping.token)
+    }
+
+    /// The character written for a token, the reverse of [`Dialect::kind`].
+    pub fn character(self, kind: &Kind) -> Option<char> {
+        self.mappings.iter().find(|mapping| mapping.token == *kind).map(|mapping| mapping.character)
+    }
+}
+
+impl Default for Dialect {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+#[derive(Debug, Clone, Copy, Error, PartialEq)]
+pub enum Error {
+    #[error("Unexpected character `{0}`, it has no meaning outside of strings and comments")]
+    UnexpectedCharacter(char),
+    #[error("Unexpected control character U+{:04X}, remove it or write it as an escape inside a string", *.0 as u32)]
+    ControlCharacter(char),
+    #[error("Unexpected invisible character U+{:04X}, it may have been pasted by accident", *.0 as u32)]
+    InvisibleCharacter(char),
+    #[error("The character `{0}` can continue an identifier but cannot start one")]
+    InvalidIdentifierStart(char),
+    #[error("Block comment is never closed, add a matching `*/`")]
+    UnterminatedBlockComment,
+    #[error("Raw string is never closed, add a `\"` followed by as many `#` as it starts with")]
+    UnterminatedRawString
+}
+
+impl Error {
+    fn from_character(character: char) -> Self {
+        match character {
+            _ if character.is_control() => Error::ControlCharacter(character),
+            '\u{00A0}' | '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' => Error::InvisibleCharacter(character),
+            _ if character.is_xid_continue() => Error::InvalidIdentifierStart(character),
+            _ => Error::UnexpectedCharacter(character)
+        }
+    }
+}
+
+/// A problem found while lexing. The lexer reports it and still produces a token so that parsing can
+/// continue past it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub error: Error,
+    pub byte_range: Range<usize>
+}
+
+/// What the lexer remembers between tokens. Lexing can resume at any token boundary given the state
+/// it had there.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct State {
+    /// The string being read when the lexer is in string mode.
+    pub string: Option<StringForm>,
+    /// The interpolations the lexer is inside, innermost last.
+    pub interpolations: Vec<Interpolation>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interpolation {
+    /// The string the interpolation returns to when it is closed.
+    pub string: StringForm,
+    /// The number of unclosed brackets inside the interpolation.
+    pub depth: usize
+}
+
 #[derive(Debug, Clone)]
 pub struct Iterator<'a> {
     source: &'a str,
-    chars: Peekable<CharIndices<'a>>
+    chars: Peekable<CharIndices<'a>>,
+    byte_base: usize,
+    state: State,
+    dialect: Dialect,
+    diagnostics: Vec<Diagnostic>
 }
 
 impl<'a> Iterator<'a> {
-    pub fn from_str(value: &'a str) -> Self { Self { source: value, chars: iter::Iterator::peekable(value.char_indices()) }}
+    pub fn from_str(value: &'a str) -> Self {
+        Self::with_dialect(value, Dialect::DEFAULT)
+    }
+
+    pub fn with_dialect(value: &'a str, dialect: Dialect) -> Self {
+        Self::resume(value, 0, State::default(), dialect)
+    }
+
+    /// Continue lexing a source from a token boundary, with the state the lexer had there.
+    pub fn resume(value: &'a str, byte_offset: usize, state: State, dialect: Dialect) -> Self {
+        Self {
+            source: value,
+            chars: iter::Iterator::peekable(value[byte_offset..].char_indices()),
+            byte_base: byte_offset,
+            state,
+            dialect,
+            diagnostics: Vec::new()
+        }
+    }
+    
     pub fn source(&self) -> &'a str { self.source }
+    pub const fn dialect(&self) -> Dialect { self.dialect }
+    pub const fn state(&self) -> &State { &self.state }
+    
+    /// Problems found in the tokens produced so far.
+    pub fn diagnostics(&self) -> &[Diagnostic] { &self.diagnostics }
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> { std::mem::take(&mut self.diagnostics) }
+
+    /// How many bytes after a token the lexer may have read to decide it. Most tokens only depend on
+    /// the character after them, but a `"` may be the start of a `"""` and a run of `#` opens a raw
+    /// string when a `"` follows it.
+    pub fn lookahead(source: &str, token: &Token) -> usize {
+        let rest = &source[token.byte_end()..];
+        match &source[token.byte_range()] {
+            "\"" => StringForm::MultiLine.quote().len() - 1,
+            "#" => rest.len() - rest.trim_start_matches('#').len() + 1,
+            _ => 1
+        }
+    }
 }
 
-impl<'a> iter::Iterator for Iterator<'a> {
-    type Item = Token<'a>;
+impl<'a> Iterator<'a> {
+    /// Lex a number literal starting at a decimal digit. The literal continues through any letters,
+    /// digits and `_`, as well as the sign of a decimal exponent, so that invalid digits are reported
+    /// against the whole literal by the parser.
+    fn next_number(&mut self, byte_start: usize) -> Token<'a> {
+        let first = self.chars.next().unwrap();
+        let mut byte_end = byte_start + first.1.len_utf8();
+        let mut radix = Radix::Decimal;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let peeked = self.chars.peek()?;
-        if let Some(mapped) = Kind::MAPPINGS.iter().find(|item| item.character == peeked.1) {
+        let prefix = if first.1 == '0' { self.chars.peek().and_then(|character| Radix::from_prefix(character.1)) } else { None };
+        if let Some(prefix) = prefix {
+            radix = prefix;
+            byte_end += self.chars.next().unwrap().1.len_utf8();
+        }
+
+        while let Some(character) = self.chars.next_if(|character| character.1.is_ascii_alphanumeric() || character.1 == '_') {
+            byte_end += character.1.len_utf8();
+
+            // the sign of a decimal exponent, as in `1,5e-9`, belongs to the literal.
+            if radix == Radix::Decimal && matches!(character.1, 'e' | 'E') {
+                if let Some(sign) = self.chars.next_if(|sign| matches!(sign.1, '-' | '+')) { byte_end += sign.1.len_utf8() }
+            }
+        }
+
+        let literal = NumberLiteral { text: &self.source[byte_start..byte_end], radix };
+        Token { kind: Kind::Number(literal), byte_start, byte_length: byte_end - byte_start }
+    }
+}
+
+impl<'a> Iterator<'a> {
+    /// Lex a comment starting at a `/`, or nothing if the `/` does not start one. Block comments nest
+    /// and an unterminated block comment runs to the end of the source. Line comments may be doc
+    /// comments.
+    fn next_comment(&mut self, byte_start: usize) -> Option<Token<'a>> {
+        let rest = &self.source[byte_start..];
+        let length = if rest.starts_with("//") {
+            rest.find(['\n', '\r']).unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            let bytes = rest.as_bytes();
+            let mut depth = 0usize;
+            let mut offset = 0usize;
+
+            // stepping over bytes is safe since `/` and `*` never appear inside a multibyte character.
+            while offset < bytes.len() {
+                if bytes[offset..].starts_with(b"/*") {
+                    depth += 1;
+                    offset += "/*".len();
+                } else if bytes[offset..].starts_with(b"*/") {
+                    depth -= 1;
+                    offset += "*/".len();
+                    if depth == 0 { break }
+                } else {
+                    offset += 1;
+                }
+            }
+
+            if depth != 0 { self.diagnostics.push(Diagnostic { error: Error::UnterminatedBlockComment, byte_range: byte_start..self.source.len() }) }
+            offset.min(rest.len())
+        } else {
+            return None;
+        };
+
+        let byte_end = byte_start + length;
+        let byte_base = self.byte_base;
+        while self.chars.next_if(|character| byte_base + character.0 < byte_end).is_some() {}
+
+        let text = &self.source[byte_start..byte_end];
+        let kind = DocComment::from_line_comment(text).map_or(Kind::Comment(text), Kind::DocComment);
+        Some(Token { kind, byte_start, byte_length: length })
+    }
+
+    /// Lex the contents of a string, where everything other than the closing quote, escapes and
+    /// interpolations is literal text.
+    fn next_string_content(&mut self, byte_start: usize, form: StringForm) -> Token<'a> {
+        if self.source[byte_start..].starts_with(form.quote()) {
+            for _ in 0..form.quote().len() { let _ = self.chars.next(); }
+            return Token { kind: form.quote_kind(), byte_start, byte_length: form.quote().len() }
+        }
+
+        let (_, first) = self.chars.next().unwrap();
+        let mut byte_end = byte_start + first.len_utf8();
+
+        let kind = match first {
+            '[' if form != StringForm::Character => Kind::InterpolationStart,
+            '\\' => {
+                let escaped = self.chars.next().map(|character| character.1);
+                byte_end += escaped.map_or(0, char::len_utf8);
+
+                // the code of a `\x..` or `\u{..}` escape belongs to it, as far as it is well formed.
+                match escaped {
+                    Some('x') => for _ in 0..2 {
+                        let Some(digit) = self.chars.next_if(|character| character.1.is_ascii_hexdigit()) else { break };
+                        byte_end += digit.1.len_utf8();
+                    },
+                    Some('u') => if let Some(brace) = self.chars.next_if(|character| character.1 == '{') {
+                        byte_end += brace.1.len_utf8();
+                        while let Some(digit) = self.chars.next_if(|character| character.1.is_ascii_hexdigit()) { byte_end += digit.1.len_utf8() }
+                        if let Some(brace) = self.chars.next_if(|character| character.1 == '}') { byte_end += brace.1.len_utf8() }
+                    },
+                    _ => ()
+                }
+
+                Kind::EscapeSequence(&self.source[byte_start..byte_end])
+            },
+            _ => {
+                while let Some(character) = self.chars.next_if(|character| !form.ends_text(character.1)) {
+                    byte_end += character.1.len_utf8();
+                }
+                Kind::StringText(&self.source[byte_start..byte_end])
+            }
+        };
+
+        Token { kind, byte_start, byte_length: byte_end - byte_start }
+    }
+
+    /// Lex a raw string starting at a `#`, or nothing if the `#` does not start one.
+    fn next_raw_string(&mut self, byte_start: usize) -> Option<Token<'a>> {
+        let string = RawString::from_text(&self.source[byte_start..])?;
+        let byte_end = byte_start + string.text.len();
+        let byte_base = self.byte_base;
+        while self.chars.next_if(|character| byte_base + character.0 < byte_end).is_some() {}
+
+        if !string.is_terminated() { self.diagnostics.push(Diagnostic { error: Error::UnterminatedRawString, byte_range: byte_start..byte_end }) }
+        Some(Token { kind: Kind::RawString(string), byte_start, byte_length: string.text.len() })
+    }
+
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        let peeked = *self.chars.peek()?;
+        let byte_start = self.byte_base + peeked.0;
+        
+        // a character literal cannot span lines, so a line break ends an unterminated one and the rest
+        // of the source is lexed normally.
+        if self.state.string == Some(StringForm::Character) && matches!(peeked.1, '\n' | '\r') { self.state.string = None }
+        if let Some(form) = self.state.string { return Some(self.next_string_content(byte_start, form)) }
+
+        // a carriage return may be the start of a `\r\n` pair, so it cannot be a single mapping.
+        if peeked.1 == '\r' {
             let _ = self.chars.next();
-            return Some(Token { kind: mapped.token, byte_length: mapped.character.len_utf8() })
+            if self.chars.next_if(|character| character.1 == '\n').is_some() {
+                return Some(Token { kind: Kind::NewLine(LineEnding::CarriageReturnLineFeed), byte_start, byte_length: "\r\n".len() })
+            }
+            return Some(Token { kind: Kind::NewLine(LineEnding::CarriageReturn), byte_start, byte_length: '\r'.len_utf8() })
         }
-        if let Some(digit) = peeked.1.to_digit(10) {
-            let character = self.chars.next().unwrap();
-            return Some(Token { kind: Kind::Digit(digit as u8), byte_length: character.1.len_utf8() })
+        if peeked.1 == '/' {
+            if let Some(comment) = self.next_comment(byte_start) { return Some(comment) }
         }
+        if peeked.1 == '#' {
+            if let Some(string) = self.next_raw_string(byte_start) { return Some(string) }
+        }
+        if peeked.1 == '"' && self.source[byte_start..].starts_with(StringForm::MultiLine.quote()) {
+            for _ in 0..StringForm::MultiLine.quote().len() { let _ = self.chars.next(); }
+            return Some(Token { kind: Kind::MultiLineQuote, byte_start, byte_length: StringForm::MultiLine.quote().len() })
+        }
+        if peeked.1 == ']' && self.state.interpolations.last().is_some_and(|interpolation| interpolation.depth == 0) {
+            let _ = self.chars.next();
+            return Some(Token { kind: Kind::InterpolationEnd, byte_start, byte_length: ']'.len_utf8() })
+        }
+        if let Some(kind) = self.dialect.kind(peeked.1) {
+            let _ = self.chars.next();
+            return Some(Token { kind, byte_start, byte_length: peeked.1.len_utf8() })
+        }
+        if peeked.1.is_ascii_digit() { return Some(self.next_number(byte_start)) }
         
-        let byte_start = peeked.0;
         let mut byte_end = byte_start;
 
-        while let Some(character) = self.chars.peek() {
-            if !character.1.is_alphabetic() && !character.1.is_alphanumeric() && character.1 != '_' { break }
-            byte_end += character.1.len_utf8();
-            let _ = self.chars.next();
+        // identifiers follow the Unicode XID rules, their first character must be XID_Start.
+        if peeked.1.is_xid_start() {
+            while let Some(character) = self.chars.next_if(|character| character.1.is_xid_continue()) {
+                byte_end += character.1.len_utf8();
+            }
         }
 
         if byte_end == byte_start { 
             let character = self.chars.next()?.1;
-            return Some(Token { kind: Kind::Other(character), byte_length: character.len_utf8() })
+            let byte_length = character.len_utf8();
+            self.diagnostics.push(Diagnostic { error: Error::from_character(character), byte_range: byte_start..byte_start + byte_length });
+            return Some(Token { kind: Kind::Other(character), byte_start, byte_length })
         }
-        Some(Token { kind: Kind::Identifier(&self.source[byte_start..byte_end]), byte_length: byte_end - byte_start })
+
+        let identifier = &self.source[byte_start..byte_end];
+        let kind = Keyword::from_identifier(identifier).map_or(Kind::Identifier(identifier), Kind::Keyword);
+        Some(Token { kind, byte_start, byte_length: byte_end - byte_start })
     }
-}
\ No newline at end of file
+}
+
+impl<'a> iter::Iterator for Iterator<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.next_token()?;
+        match token.kind {
+            Kind::StringQuote => self.state.string = if self.state.string.is_some() { None } else { Some(StringForm::Quoted) },
+            Kind::MultiLineQuote => self.state.string = if self.state.string.is_some() { None } else { Some(StringForm::MultiLine) },
+            Kind::CharacterQuote => self.state.string = if self.state.string.is_some() { None } else { Some(StringForm::Character) },
+            Kind::InterpolationStart => {
+                let string = self.state.string.take().unwrap_or_default();
+                self.state.interpolations.push(Interpolation { string, depth: 0 });
+            },
+            Kind::InterpolationEnd => self.state.string = self.state.interpolations.pop().map(|interpolation| interpolation.string),
+            Kind::OpeningBracket => if let Some(interpolation) = self.state.interpolations.last_mut() { interpolation.depth += 1 },
+            Kind::ClosingBracket => if let Some(interpolation) = self.state.interpolations.last_mut() { interpolation.depth -= 1 },
+            _ => ()
+        }
+
+        Some(token)
+    }
+}
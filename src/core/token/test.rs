@@ -0,0 +1,100 @@
+use crate::core::token::{Diagnostic, Dialect, DocComment, DocTarget, Error, Iterator, Kind, LineEnding, NumberLiteral, Radix, RawString};
+
+#[test]
+fn comment() {
+    let source = "/// Uses [vector<u8>] internally.\n/* outer /* inner */ still */ x / y";
+    let kinds = Iterator::from_str(source).map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds[0], Kind::DocComment(DocComment {
+        target: DocTarget::Item,
+        text: "/// Uses [vector<u8>] internally.",
+        content: "Uses [vector<u8>] internally."
+    }));
+    assert_eq!(kinds[2], Kind::Comment("/* outer /* inner */ still */"));
+    assert_eq!(kinds[6], Kind::Other('/'));
+}
+
+#[test]
+fn comment_in_string() {
+    let kinds = Iterator::from_str("\"a\\\"//b\" //c").map(|token| *token.kind()).collect::<Vec<_>>();
+    assert!(!kinds[..kinds.len() - 1].iter().any(|kind| matches!(kind, Kind::Comment(_))));
+    assert_eq!(kinds.last(), Some(&Kind::Comment("//c")));
+}
+
+#[test]
+fn non_ascii_block_comment() {
+    let kinds = Iterator::from_str("/* café /* ü */ 😀 */x").map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds, [Kind::Comment("/* café /* ü */ 😀 */"), Kind::Identifier("x")]);
+}
+
+#[test]
+fn doc_comment() {
+    assert_eq!(DocComment::from_line_comment("//! Math").map(|comment| (comment.target, comment.content)), Some((DocTarget::Module, "Math")));
+    assert_eq!(DocComment::from_line_comment("//// divider"), None);
+    assert_eq!(DocComment::from_line_comment("// plain"), None);
+}
+
+#[test]
+fn diagnostics() {
+    let mut tokens = Iterator::from_str("a @ \"@\" \u{0007} /* open");
+    let kinds = tokens.by_ref().map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds[2], Kind::Other('@'));
+    assert_eq!(tokens.diagnostics(), [
+        Diagnostic { error: Error::UnexpectedCharacter('@'), byte_range: 2..3 },
+        Diagnostic { error: Error::ControlCharacter('\u{0007}'), byte_range: 8..9 },
+        Diagnostic { error: Error::UnterminatedBlockComment, byte_range: 10..17 }
+    ]);
+}
+
+#[test]
+fn string_mode() {
+    let kinds = Iterator::from_str("\"v = [f[\"x\"][0]] \\[\\u{1F600}\\\"\" x").map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds, [
+        Kind::StringQuote,
+        Kind::StringText("v = "),
+        Kind::InterpolationStart,
+        Kind::Identifier("f"),
+        Kind::OpeningBracket,
+        Kind::StringQuote,
+        Kind::StringText("x"),
+        Kind::StringQuote,
+        Kind::ClosingBracket,
+        Kind::OpeningBracket,
+        Kind::Number(NumberLiteral { text: "0", radix: Radix::Decimal }),
+        Kind::ClosingBracket,
+        Kind::InterpolationEnd,
+        Kind::StringText(" "),
+        Kind::EscapeSequence("\\["),
+        Kind::EscapeSequence("\\u{1F600}"),
+        Kind::EscapeSequence("\\\""),
+        Kind::StringQuote,
+        Kind::Space,
+        Kind::Identifier("x")
+    ]);
+}
+
+#[test]
+fn line_break_in_string() {
+    let kinds = Iterator::from_str("\"a\r\nb\"\r\n").map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds, [Kind::StringQuote, Kind::StringText("a\r\nb"), Kind::StringQuote, Kind::NewLine(LineEnding::CarriageReturnLineFeed)]);
+}
+
+#[test]
+fn raw_string() {
+    let mut tokens = Iterator::from_str("#print #\"a\\\"# ##\"open");
+    let kinds = tokens.by_ref().map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds[0], Kind::Macro);
+    assert_eq!(kinds[3], Kind::RawString(RawString { delimiters: 1, text: "#\"a\\\"#", content: "a\\" }));
+    assert_eq!(tokens.diagnostics(), [Diagnostic { error: Error::UnterminatedRawString, byte_range: 14..21 }]);
+}
+
+#[test]
+fn dialect() {
+    let number = |text| Kind::Number(NumberLiteral { text, radix: Radix::Decimal });
+    let kinds = Iterator::with_dialect("1.5;", Dialect::DOT_DECIMAL).map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds, [number("1"), Kind::Decimal, number("5"), Kind::Stop]);
+
+    let kinds = Iterator::from_str("1,5.").map(|token| *token.kind()).collect::<Vec<_>>();
+    assert_eq!(kinds, [number("1"), Kind::Decimal, number("5"), Kind::Stop]);
+    assert_eq!(Dialect::DOT_DECIMAL.character(&Kind::Stop), Some(';'));
+    assert_eq!(Dialect::DOT_DECIMAL.kind(','), None);
+}